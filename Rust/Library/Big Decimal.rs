@@ -0,0 +1,783 @@
+// Requires Big Integer.rs for BigInt and BigUint
+// Every rounding in this file goes to the nearest value, ties away from zero
+
+fn pow10(k: usize) -> BigUint {
+    let mut limbs = vec![0; k / BASE_DIGITS];
+    limbs.push(10u32.pow((k % BASE_DIGITS) as u32));
+
+    BigUint { limbs }
+}
+
+fn mul_pow10(x: &BigInt, k: usize) -> BigInt {
+    if x.is_zero() {
+        return x.clone();
+    }
+
+    let mut limbs = vec![0; k / BASE_DIGITS];
+    limbs.extend(mul_small(
+        &x.magnitude.limbs,
+        10u32.pow((k % BASE_DIGITS) as u32),
+    ));
+
+    BigInt::from_parts(x.negative, BigUint { limbs })
+}
+
+// Truncates toward zero
+fn div_pow10(x: &BigInt, k: usize) -> BigInt {
+    let limbs = x.magnitude.limbs.get(k / BASE_DIGITS..).unwrap_or(&[]);
+    let (limbs, _) = div_rem_small(limbs, 10u32.pow((k % BASE_DIGITS) as u32));
+
+    BigInt::from_parts(x.negative, BigUint { limbs })
+}
+
+fn div_round(num: &BigInt, den: &BigInt) -> BigInt {
+    let (q, r) = num.magnitude.div_rem(&den.magnitude);
+    let q = if &r + &r >= den.magnitude {
+        &q + &BigUint::from(1)
+    } else {
+        q
+    };
+
+    BigInt::from_parts(num.negative != den.negative, q)
+}
+
+// Moves a fixed-point value from scale `from` to scale `to`, rounding if digits are dropped
+fn rescale(x: &BigInt, from: usize, to: usize) -> BigInt {
+    if to >= from {
+        mul_pow10(x, to - from)
+    } else {
+        div_round(x, &BigInt::from(pow10(from - to)))
+    }
+}
+
+fn gcd_big(a: &BigUint, b: &BigUint) -> BigUint {
+    let mut a = a.clone();
+    let mut b = b.clone();
+
+    while !b.is_zero() {
+        let r = &a % &b;
+        a = b;
+        b = r;
+    }
+
+    a
+}
+
+#[derive(Debug)]
+struct ParseBigDecimalError;
+
+impl From<ParseBigIntError> for ParseBigDecimalError {
+    fn from(_: ParseBigIntError) -> Self {
+        ParseBigDecimalError
+    }
+}
+
+// Exact fraction kept in lowest terms with a positive denominator
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct BigRational {
+    num: BigInt,
+    den: BigUint,
+}
+
+impl BigRational {
+    fn new(num: BigInt, den: BigInt) -> Self {
+        assert!(!den.is_zero(), "Zero denominator");
+
+        let g = gcd_big(&num.magnitude, &den.magnitude);
+
+        Self {
+            num: BigInt::from_parts(num.negative != den.negative, &num.magnitude / &g),
+            den: &den.magnitude / &g,
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.num.is_zero()
+    }
+
+    fn recip(&self) -> Self {
+        Self::new(BigInt::from(self.den.clone()), self.num.clone())
+    }
+
+    fn pow(&self, exp: i32) -> Self {
+        let ret = Self {
+            num: self.num.pow(exp.unsigned_abs()),
+            den: self.den.pow(exp.unsigned_abs()),
+        };
+
+        if exp < 0 {
+            ret.recip()
+        } else {
+            ret
+        }
+    }
+
+    // Largest integer not above the value
+    fn floor(&self) -> BigInt {
+        let (q, r) = self.num.magnitude.div_rem(&self.den);
+
+        if self.num.negative && !r.is_zero() {
+            BigInt::from_parts(true, &q + &BigUint::from(1))
+        } else {
+            BigInt::from_parts(self.num.negative, q)
+        }
+    }
+
+    fn to_decimal(&self, scale: usize) -> BigDecimal {
+        BigDecimal {
+            value: div_round(
+                &mul_pow10(&self.num, scale),
+                &BigInt::from(self.den.clone()),
+            ),
+            scale,
+        }
+    }
+}
+
+impl From<i64> for BigRational {
+    fn from(val: i64) -> Self {
+        Self {
+            num: BigInt::from(val),
+            den: BigUint::from(1),
+        }
+    }
+}
+
+impl From<BigInt> for BigRational {
+    fn from(val: BigInt) -> Self {
+        Self {
+            num: val,
+            den: BigUint::from(1),
+        }
+    }
+}
+
+impl PartialOrd for BigRational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigRational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let left = &self.num * &BigInt::from(other.den.clone());
+        let right = &other.num * &BigInt::from(self.den.clone());
+
+        left.cmp(&right)
+    }
+}
+
+impl std::ops::Neg for &BigRational {
+    type Output = BigRational;
+
+    fn neg(self) -> BigRational {
+        BigRational {
+            num: -&self.num,
+            den: self.den.clone(),
+        }
+    }
+}
+
+impl std::ops::Add for &BigRational {
+    type Output = BigRational;
+
+    fn add(self, other: &BigRational) -> BigRational {
+        BigRational::new(
+            &(&self.num * &BigInt::from(other.den.clone()))
+                + &(&other.num * &BigInt::from(self.den.clone())),
+            BigInt::from(&self.den * &other.den),
+        )
+    }
+}
+
+impl std::ops::Sub for &BigRational {
+    type Output = BigRational;
+
+    fn sub(self, other: &BigRational) -> BigRational {
+        self + &-other
+    }
+}
+
+impl std::ops::Mul for &BigRational {
+    type Output = BigRational;
+
+    fn mul(self, other: &BigRational) -> BigRational {
+        BigRational::new(&self.num * &other.num, BigInt::from(&self.den * &other.den))
+    }
+}
+
+impl std::ops::Div for &BigRational {
+    type Output = BigRational;
+
+    fn div(self, other: &BigRational) -> BigRational {
+        BigRational::new(
+            &self.num * &BigInt::from(other.den.clone()),
+            &other.num * &BigInt::from(self.den.clone()),
+        )
+    }
+}
+
+// Accepts integers, fractions like -3/4 and decimals like 1.25
+impl FromStr for BigRational {
+    type Err = ParseBigDecimalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((num, den)) = s.split_once('/') {
+            let den = den.parse::<BigInt>()?;
+
+            if den.is_zero() {
+                return Err(ParseBigDecimalError);
+            }
+
+            return Ok(Self::new(num.parse()?, den));
+        }
+
+        Ok(s.parse::<BigDecimal>()?.to_rational())
+    }
+}
+
+impl fmt::Display for BigRational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.den == BigUint::from(1) {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+impl fmt::Debug for BigRational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
+// Fixed-point helpers where every value stands for x / 10^w
+fn fixed_mul(a: &BigInt, b: &BigInt, w: usize) -> BigInt {
+    div_pow10(&(a * b), w)
+}
+
+fn fixed_div(a: &BigInt, b: &BigInt, w: usize) -> BigInt {
+    &mul_pow10(a, w) / b
+}
+
+fn fixed_sqrt(x: &BigInt, w: usize) -> BigInt {
+    mul_pow10(x, w).sqrt()
+}
+
+fn fixed_exp(x: &BigInt, w: usize) -> BigInt {
+    // Halving until |x| < 1 / 8 costs a factor of 2 in accuracy per squaring
+    let mut m = 0;
+    let mut bound = BigInt::from(pow10(w));
+
+    while x.magnitude > bound.magnitude {
+        bound = &bound + &bound;
+        m += 1;
+    }
+
+    let m = m + 3;
+    let w2 = w + m / 3 + 2;
+    let one = BigInt::from(pow10(w2));
+    let r = div_round(
+        &mul_pow10(x, w2 - w),
+        &BigInt::from(BigUint::from(2).pow(m as u32)),
+    );
+
+    let mut sum = one.clone();
+    let mut term = one;
+    let mut i = 1;
+
+    loop {
+        term = &fixed_mul(&term, &r, w2) / &BigInt::from(i);
+
+        if term.is_zero() {
+            break;
+        }
+
+        sum = &sum + &term;
+        i += 1;
+    }
+
+    for _ in 0..m {
+        sum = fixed_mul(&sum, &sum, w2);
+    }
+
+    rescale(&sum, w2, w)
+}
+
+// x must be positive
+fn fixed_ln(x: &BigInt, w: usize) -> BigInt {
+    // Square roots bring x into [1 / 2, 2], every one of them doubles the later error
+    let w2 = w + 12;
+    let one = BigInt::from(pow10(w2));
+    let two = &one + &one;
+    let half = &one / &BigInt::from(2);
+    let mut x = mul_pow10(x, w2 - w);
+    let mut k = 0;
+
+    while x > two || x < half {
+        x = fixed_sqrt(&x, w2);
+        k += 1;
+    }
+
+    // ln x = 2 atanh((x - 1) / (x + 1))
+    let y = fixed_div(&(&x - &one), &(&x + &one), w2);
+    let y2 = fixed_mul(&y, &y, w2);
+    let mut sum = BigInt::from(0);
+    let mut term = y;
+    let mut i = 1;
+
+    while !term.is_zero() {
+        sum = &sum + &(&term / &BigInt::from(i));
+        term = fixed_mul(&term, &y2, w2);
+        i += 2;
+    }
+
+    let sum = &sum * &BigInt::from(BigUint::from(2).pow(k + 1));
+
+    rescale(&sum, w2, w)
+}
+
+// Sum of (-1)^k / ((2k + 1) n^(2k + 1)), which is atan(1 / n)
+fn fixed_atan_inv(n: i64, w: usize) -> BigInt {
+    let n2 = BigInt::from(n * n);
+    let mut term = &BigInt::from(pow10(w)) / &BigInt::from(n);
+    let mut sum = BigInt::from(0);
+    let mut k = 0;
+
+    while !term.is_zero() {
+        let t = &term / &BigInt::from(2 * k + 1);
+        sum = if k % 2 == 0 { &sum + &t } else { &sum - &t };
+        term = &term / &n2;
+        k += 1;
+    }
+
+    sum
+}
+
+// Reference: https://en.wikipedia.org/wiki/Machin-like_formula
+fn fixed_pi(w: usize) -> BigInt {
+    let w2 = w + 5;
+    let pi = &(&fixed_atan_inv(5, w2) * &BigInt::from(16))
+        - &(&fixed_atan_inv(239, w2) * &BigInt::from(4));
+
+    rescale(&pi, w2, w)
+}
+
+// (sin x, cos x) by Taylor series, x should be at most around pi
+fn fixed_sin_cos(x: &BigInt, w: usize) -> (BigInt, BigInt) {
+    let mut sin = BigInt::from(0);
+    let mut cos = BigInt::from(0);
+    let mut term = BigInt::from(pow10(w));
+    let mut i = 0;
+
+    while !term.is_zero() {
+        match i % 4 {
+            0 => cos = &cos + &term,
+            1 => sin = &sin + &term,
+            2 => cos = &cos - &term,
+            _ => sin = &sin - &term,
+        }
+
+        i += 1;
+        term = &fixed_mul(&term, x, w) / &BigInt::from(i);
+    }
+
+    (sin, cos)
+}
+
+fn fixed_atan(x: &BigInt, w: usize) -> BigInt {
+    let one = BigInt::from(pow10(w));
+
+    if x.magnitude > one.magnitude {
+        let half_pi = &fixed_pi(w) / &BigInt::from(2);
+        let ret = &half_pi - &fixed_atan(&fixed_div(&one, &x.abs(), w), w);
+
+        return if x.negative { -ret } else { ret };
+    }
+
+    // atan x = 2 atan(x / (1 + sqrt(1 + x^2))) until |x| < 1 / 10
+    let w2 = w + 5;
+    let one = BigInt::from(pow10(w2));
+    let tenth = BigInt::from(pow10(w2 - 1));
+    let mut x = mul_pow10(x, w2 - w);
+    let mut k = 0;
+
+    while x.magnitude > tenth.magnitude {
+        let root = fixed_sqrt(&(&one + &fixed_mul(&x, &x, w2)), w2);
+        x = fixed_div(&x, &(&one + &root), w2);
+        k += 1;
+    }
+
+    let x2 = fixed_mul(&x, &x, w2);
+    let mut sum = BigInt::from(0);
+    let mut term = x;
+    let mut i = 1;
+
+    while !term.is_zero() {
+        let t = &term / &BigInt::from(i);
+        sum = if i % 4 == 1 { &sum + &t } else { &sum - &t };
+        term = fixed_mul(&term, &x2, w2);
+        i += 2;
+    }
+
+    rescale(&(&sum * &BigInt::from(1i64 << k)), w2, w)
+}
+
+// Ziv's strategy, evaluates with more guard digits until rounding to the scale is certain
+// compute(w) returns the value times 10^w, off by much less than 10^(w - scale) / 2
+fn round_with_guard(scale: usize, compute: impl Fn(usize) -> BigInt) -> BigInt {
+    let mut guard = 16;
+
+    loop {
+        let v = compute(scale + guard);
+        let unit = pow10(guard);
+        let (q, r) = v.magnitude.div_rem(&unit);
+        let twice = &r + &r;
+        let distance = if twice >= unit {
+            &twice - &unit
+        } else {
+            &unit - &twice
+        };
+
+        if distance > pow10(guard / 2) || guard >= 1024 {
+            let q = if twice >= unit {
+                &q + &BigUint::from(1)
+            } else {
+                q
+            };
+
+            return BigInt::from_parts(v.negative, q);
+        }
+
+        guard *= 2;
+    }
+}
+
+// value / 10^scale, where scale is the number of digits after the decimal point
+#[derive(Clone)]
+struct BigDecimal {
+    value: BigInt,
+    scale: usize,
+}
+
+impl BigDecimal {
+    fn new(value: BigInt, scale: usize) -> Self {
+        Self { value, scale }
+    }
+
+    fn with_scale(&self, scale: usize) -> Self {
+        Self {
+            value: rescale(&self.value, self.scale, scale),
+            scale,
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    fn abs(&self) -> Self {
+        Self::new(self.value.abs(), self.scale)
+    }
+
+    fn to_rational(&self) -> BigRational {
+        BigRational::new(self.value.clone(), BigInt::from(pow10(self.scale)))
+    }
+
+    // Number of digits before the decimal point
+    fn int_digits(&self) -> usize {
+        let digits = self.value.magnitude.to_string().len();
+        digits.saturating_sub(self.scale)
+    }
+
+    // Number of zeros right after the decimal point when |x| < 1
+    fn leading_zeros(&self) -> usize {
+        if self.is_zero() {
+            return self.scale;
+        }
+
+        let digits = self.value.magnitude.to_string().len();
+        self.scale.saturating_sub(digits)
+    }
+
+    // Input at working scale w, exact whenever w covers the scale of the input
+    fn at(&self, w: usize) -> BigInt {
+        rescale(&self.value, self.scale, w)
+    }
+
+    fn pi(scale: usize) -> Self {
+        Self::new(round_with_guard(scale, fixed_pi), scale)
+    }
+
+    // Correctly rounded without guard digits, as a tie would need an odd square
+    fn sqrt(&self) -> Self {
+        assert!(!self.value.negative, "Square root of a negative number");
+
+        let n = mul_pow10(&self.value, self.scale);
+        let r = n.sqrt();
+        let twice = &(&r + &r) + &BigInt::from(1);
+        let n4 = &n * &BigInt::from(4);
+
+        Self::new(
+            if &twice * &twice <= n4 {
+                &r + &BigInt::from(1)
+            } else {
+                r
+            },
+            self.scale,
+        )
+    }
+
+    fn exp(&self) -> Self {
+        // exp x has about x * log10(e) digits before the point, each costs one more digit
+        let approx = self.to_string().parse::<f64>().unwrap_or(0.0);
+        let extra = if approx > 0.0 {
+            (approx * std::f64::consts::LOG10_E) as usize + 1
+        } else {
+            0
+        };
+
+        let value = round_with_guard(self.scale, |w| {
+            let w2 = (w + extra).max(self.scale);
+            rescale(&fixed_exp(&self.at(w2), w2), w2, w)
+        });
+
+        Self::new(value, self.scale)
+    }
+
+    // Natural logarithm, panics unless positive
+    fn ln(&self) -> Self {
+        assert!(
+            !self.value.negative && !self.is_zero(),
+            "Logarithm of a non-positive number"
+        );
+
+        let extra = self.leading_zeros() + 1;
+        let value = round_with_guard(self.scale, |w| {
+            let w2 = (w + extra).max(self.scale);
+            rescale(&fixed_ln(&self.at(w2), w2), w2, w)
+        });
+
+        Self::new(value, self.scale)
+    }
+
+    // Reduces x modulo 2 pi, which needs one more digit per digit of x
+    fn sin_cos_at(&self, w: usize) -> (BigInt, BigInt) {
+        let w2 = (w + self.int_digits() + 2).max(self.scale);
+        let x = self.at(w2);
+        let two_pi = &fixed_pi(w2) * &BigInt::from(2);
+        let r = &x - &(&div_round(&x, &two_pi) * &two_pi);
+        let (sin, cos) = fixed_sin_cos(&r, w2);
+
+        (rescale(&sin, w2, w), rescale(&cos, w2, w))
+    }
+
+    fn sin(&self) -> Self {
+        Self::new(
+            round_with_guard(self.scale, |w| self.sin_cos_at(w).0),
+            self.scale,
+        )
+    }
+
+    fn cos(&self) -> Self {
+        Self::new(
+            round_with_guard(self.scale, |w| self.sin_cos_at(w).1),
+            self.scale,
+        )
+    }
+
+    fn tan(&self) -> Self {
+        let value = round_with_guard(self.scale, |w| {
+            // A small cosine magnifies the error of the quotient by 1 / cos^2
+            let (_, cos) = self.sin_cos_at(w);
+            let zeros = w.saturating_sub(cos.magnitude.to_string().len());
+            let w2 = w + 2 * zeros + 2;
+            let (sin, cos) = self.sin_cos_at(w2);
+
+            rescale(&fixed_div(&sin, &cos, w2), w2, w)
+        });
+
+        Self::new(value, self.scale)
+    }
+
+    fn atan(&self) -> Self {
+        let value = round_with_guard(self.scale, |w| {
+            let w2 = (w + 2).max(self.scale);
+            rescale(&fixed_atan(&self.at(w2), w2), w2, w)
+        });
+
+        Self::new(value, self.scale)
+    }
+
+    // |x| <= 1, asin x = atan(x / sqrt(1 - x^2))
+    fn asin_at(&self, w: usize) -> BigInt {
+        let one = BigDecimal::new(BigInt::from(1), 0);
+        let gap = &one - &self.abs();
+
+        assert!(!gap.value.negative, "asin of a number outside [-1, 1]");
+
+        // Near 1 the square root in the denominator loses the zeros of 1 - |x|
+        let w2 = (w + gap.leading_zeros() + 4).max(self.scale);
+
+        if gap.is_zero() {
+            let half_pi = rescale(&(&fixed_pi(w2) / &BigInt::from(2)), w2, w);
+
+            return if self.value.negative {
+                -half_pi
+            } else {
+                half_pi
+            };
+        }
+
+        let x = self.at(w2);
+        let root = fixed_sqrt(&(&BigInt::from(pow10(w2)) - &fixed_mul(&x, &x, w2)), w2);
+
+        rescale(&fixed_atan(&fixed_div(&x, &root, w2), w2), w2, w)
+    }
+
+    fn asin(&self) -> Self {
+        Self::new(
+            round_with_guard(self.scale, |w| self.asin_at(w)),
+            self.scale,
+        )
+    }
+
+    fn acos(&self) -> Self {
+        let value = round_with_guard(self.scale, |w| {
+            let half_pi = &fixed_pi(w + 1) / &BigInt::from(2);
+            &rescale(&half_pi, w + 1, w) - &self.asin_at(w)
+        });
+
+        Self::new(value, self.scale)
+    }
+}
+
+impl From<i64> for BigDecimal {
+    fn from(val: i64) -> Self {
+        Self::new(BigInt::from(val), 0)
+    }
+}
+
+impl PartialEq for BigDecimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for BigDecimal {}
+
+impl PartialOrd for BigDecimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigDecimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        self.at(scale).cmp(&other.at(scale))
+    }
+}
+
+impl std::ops::Neg for &BigDecimal {
+    type Output = BigDecimal;
+
+    fn neg(self) -> BigDecimal {
+        BigDecimal::new(-&self.value, self.scale)
+    }
+}
+
+// Results keep the larger scale of the two operands
+impl std::ops::Add for &BigDecimal {
+    type Output = BigDecimal;
+
+    fn add(self, other: &BigDecimal) -> BigDecimal {
+        let scale = self.scale.max(other.scale);
+        BigDecimal::new(&self.at(scale) + &other.at(scale), scale)
+    }
+}
+
+impl std::ops::Sub for &BigDecimal {
+    type Output = BigDecimal;
+
+    fn sub(self, other: &BigDecimal) -> BigDecimal {
+        let scale = self.scale.max(other.scale);
+        BigDecimal::new(&self.at(scale) - &other.at(scale), scale)
+    }
+}
+
+impl std::ops::Mul for &BigDecimal {
+    type Output = BigDecimal;
+
+    fn mul(self, other: &BigDecimal) -> BigDecimal {
+        let scale = self.scale.max(other.scale);
+        let value = rescale(
+            &(&self.value * &other.value),
+            self.scale + other.scale,
+            scale,
+        );
+
+        BigDecimal::new(value, scale)
+    }
+}
+
+impl std::ops::Div for &BigDecimal {
+    type Output = BigDecimal;
+
+    fn div(self, other: &BigDecimal) -> BigDecimal {
+        assert!(!other.is_zero(), "Division by zero");
+
+        let scale = self.scale.max(other.scale);
+        let num = mul_pow10(&self.value, scale + other.scale - self.scale);
+
+        BigDecimal::new(div_round(&num, &other.value), scale)
+    }
+}
+
+impl FromStr for BigDecimal {
+    type Err = ParseBigDecimalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+        let (negative, int) = match int.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, int.strip_prefix('+').unwrap_or(int)),
+        };
+
+        // BigUint accepts a leading '+', which must not follow the sign stripped above
+        if int.starts_with('+')
+            || (int.is_empty() && frac.is_empty())
+            || !frac.bytes().all(|c| c.is_ascii_digit())
+        {
+            return Err(ParseBigDecimalError);
+        }
+
+        let magnitude = format!("{int}{frac}").parse::<BigUint>()?;
+
+        Ok(Self::new(
+            BigInt::from_parts(negative, magnitude),
+            frac.len(),
+        ))
+    }
+}
+
+impl fmt::Display for BigDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = format!("{:0>width$}", self.value.magnitude, width = self.scale + 1);
+        let (int, frac) = digits.split_at(digits.len() - self.scale);
+        let ret = if self.scale == 0 {
+            int.to_string()
+        } else {
+            format!("{int}.{frac}")
+        };
+
+        f.pad_integral(!self.value.negative, "", &ret)
+    }
+}
+
+impl fmt::Debug for BigDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{self}")
+    }
+}
@@ -0,0 +1,899 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+// Little-endian limbs in base 10^9, so decimal parsing and printing are linear
+const BASE: u64 = 1_000_000_000;
+const BASE_DIGITS: usize = 9;
+const KARATSUBA_THRESHOLD: usize = 32;
+const NEWTON_THRESHOLD: usize = 64;
+const NTT_THRESHOLD: usize = 256;
+const NTT_MAX_LEN: usize = 1 << 24;
+
+fn trim(v: &mut Vec<u32>) {
+    while v.last() == Some(&0) {
+        v.pop();
+    }
+}
+
+fn trimmed(v: &[u32]) -> Vec<u32> {
+    let mut ret = v.to_vec();
+    trim(&mut ret);
+    ret
+}
+
+// Both sides must be trimmed
+fn cmp_limbs(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+// ret += b * BASE^shift, ret must be long enough to hold the sum
+fn add_at(ret: &mut [u32], b: &[u32], shift: usize) {
+    let mut carry = 0;
+    let mut i = 0;
+
+    while i < b.len() || carry > 0 {
+        let cur = ret[shift + i] as u64 + b.get(i).copied().unwrap_or(0) as u64 + carry;
+        ret[shift + i] = (cur % BASE) as u32;
+        carry = cur / BASE;
+        i += 1;
+    }
+}
+
+fn add_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut ret = a.to_vec();
+    ret.resize(a.len().max(b.len()) + 1, 0);
+
+    add_at(&mut ret, b, 0);
+    trim(&mut ret);
+    ret
+}
+
+// a - b, a must not be smaller than b
+fn sub_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut ret = a.to_vec();
+    let mut borrow = 0;
+    let mut i = 0;
+
+    while i < b.len() || borrow > 0 {
+        let mut cur = ret[i] as i64 - b.get(i).copied().unwrap_or(0) as i64 - borrow;
+        borrow = 0;
+
+        if cur < 0 {
+            cur += BASE as i64;
+            borrow = 1;
+        }
+
+        ret[i] = cur as u32;
+        i += 1;
+    }
+
+    trim(&mut ret);
+    ret
+}
+
+fn mul_small(a: &[u32], b: u32) -> Vec<u32> {
+    let mut ret = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0;
+
+    for &x in a.iter() {
+        let cur = x as u64 * b as u64 + carry;
+        ret.push((cur % BASE) as u32);
+        carry = cur / BASE;
+    }
+
+    ret.push(carry as u32);
+    trim(&mut ret);
+    ret
+}
+
+fn div_rem_small(a: &[u32], b: u32) -> (Vec<u32>, u32) {
+    let mut ret = vec![0; a.len()];
+    let mut rem = 0;
+
+    for i in (0..a.len()).rev() {
+        let cur = rem * BASE + a[i] as u64;
+        ret[i] = (cur / b as u64) as u32;
+        rem = cur % b as u64;
+    }
+
+    trim(&mut ret);
+    (ret, rem as u32)
+}
+
+fn mul_schoolbook(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut ret = vec![0; a.len() + b.len()];
+
+    for (i, &x) in a.iter().enumerate() {
+        if x == 0 {
+            continue;
+        }
+
+        let mut carry = 0;
+
+        for (j, &y) in b.iter().enumerate() {
+            let cur = ret[i + j] as u64 + x as u64 * y as u64 + carry;
+            ret[i + j] = (cur % BASE) as u32;
+            carry = cur / BASE;
+        }
+
+        let mut k = i + b.len();
+
+        while carry > 0 {
+            let cur = ret[k] as u64 + carry;
+            ret[k] = (cur % BASE) as u32;
+            carry = cur / BASE;
+            k += 1;
+        }
+    }
+
+    trim(&mut ret);
+    ret
+}
+
+fn pow_mod_prime(mut x: u64, mut y: u64, p: u64) -> u64 {
+    let mut ret = 1;
+
+    while y > 0 {
+        if y & 1 != 0 {
+            ret = ret * x % p;
+        }
+
+        x = x * x % p;
+        y >>= 1;
+    }
+
+    ret
+}
+
+// The prime and its primitive root are constants so that every % compiles to a multiplication
+fn process_ntt_limbs<const P: u64, const G: u64>(a: &mut [u64], is_reverse: bool) {
+    let n = a.len();
+    let mut j = 0;
+
+    for i in 1..n {
+        let mut bit = n >> 1;
+
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+
+        j ^= bit;
+
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+
+    while len <= n {
+        let mut w = pow_mod_prime(G, (P - 1) / len as u64, P);
+
+        if is_reverse {
+            w = pow_mod_prime(w, P - 2, P);
+        }
+
+        let mut roots = vec![1; len / 2];
+
+        for k in 1..len / 2 {
+            roots[k] = roots[k - 1] * w % P;
+        }
+
+        for chunk in a.chunks_mut(len) {
+            let (left, right) = chunk.split_at_mut(len / 2);
+
+            for k in 0..len / 2 {
+                let u = left[k];
+                let v = right[k] * roots[k] % P;
+
+                left[k] = if u + v >= P { u + v - P } else { u + v };
+                right[k] = if u >= v { u - v } else { u + P - v };
+            }
+        }
+
+        len <<= 1;
+    }
+
+    if is_reverse {
+        let n_inv = pow_mod_prime(n as u64, P - 2, P);
+
+        for x in a.iter_mut() {
+            *x = *x * n_inv % P;
+        }
+    }
+}
+
+fn convolution_limbs<const P: u64, const G: u64>(a: &[u32], b: &[u32]) -> Vec<u64> {
+    let n = (a.len() + b.len() - 1).next_power_of_two();
+    let mut fa = a.iter().map(|&x| x as u64 % P).collect::<Vec<_>>();
+    let mut fb = b.iter().map(|&x| x as u64 % P).collect::<Vec<_>>();
+    fa.resize(n, 0);
+    fb.resize(n, 0);
+
+    process_ntt_limbs::<P, G>(&mut fa, false);
+    process_ntt_limbs::<P, G>(&mut fb, false);
+
+    for i in 0..n {
+        fa[i] = fa[i] * fb[i] % P;
+    }
+
+    process_ntt_limbs::<P, G>(&mut fa, true);
+    fa
+}
+
+// P3 = 45 * 2^24 + 1 caps the transform at 2^24 limbs, and below that every coefficient
+// stays under 2^24 * 10^18 < 5.9 * 10^25, so the three primes hold it exactly
+fn mul_ntt(a: &[u32], b: &[u32]) -> Vec<u32> {
+    assert!(
+        a.len() + b.len() <= NTT_MAX_LEN,
+        "Operands too long for the NTT"
+    );
+
+    const P1: u64 = 167_772_161;
+    const P2: u64 = 469_762_049;
+    const P3: u64 = 754_974_721;
+
+    let c1 = convolution_limbs::<P1, 3>(a, b);
+    let c2 = convolution_limbs::<P2, 3>(a, b);
+    let c3 = convolution_limbs::<P3, 11>(a, b);
+
+    // Garner's algorithm
+    let p1_inv = pow_mod_prime(P1, P2 - 2, P2);
+    let p12_inv = pow_mod_prime(P1 * P2 % P3, P3 - 2, P3);
+    let mut ret = Vec::with_capacity(a.len() + b.len());
+    let mut carry = 0u128;
+
+    for i in 0..a.len() + b.len() - 1 {
+        let t1 = c1[i];
+        let t2 = (c2[i] + P2 - t1 % P2) % P2 * p1_inv % P2;
+        let t3 = (c3[i] + 2 * P3 - (t1 + P1 * t2 % P3) % P3) % P3 * p12_inv % P3;
+        let cur = t1 as u128 + P1 as u128 * (t2 as u128 + P2 as u128 * t3 as u128) + carry;
+
+        ret.push((cur % BASE as u128) as u32);
+        carry = cur / BASE as u128;
+    }
+
+    ret.extend(u128_to_limbs(carry));
+    trim(&mut ret);
+    ret
+}
+
+// Reference: https://en.wikipedia.org/wiki/Karatsuba_algorithm
+fn mul_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+
+    if b.is_empty() {
+        return Vec::new();
+    }
+
+    if b.len() < KARATSUBA_THRESHOLD {
+        return mul_schoolbook(a, b);
+    }
+
+    // Longer products fall through to Karatsuba until the halves fit the NTT
+    if b.len() >= NTT_THRESHOLD && a.len() + b.len() <= NTT_MAX_LEN {
+        return mul_ntt(a, b);
+    }
+
+    let mut ret = vec![0; a.len() + b.len() + 1];
+
+    // Unbalanced operands are cut into pieces as long as the shorter one
+    if a.len() >= 2 * b.len() {
+        for (i, chunk) in a.chunks(b.len()).enumerate() {
+            add_at(&mut ret, &mul_limbs(&trimmed(chunk), b), i * b.len());
+        }
+
+        trim(&mut ret);
+        return ret;
+    }
+
+    let half = a.len() / 2;
+    let (a0, a1) = (trimmed(&a[..half]), &a[half..]);
+    let (b0, b1) = (trimmed(&b[..half]), &b[half..]);
+
+    let z0 = mul_limbs(&a0, &b0);
+    let z2 = mul_limbs(a1, b1);
+    let z1 = mul_limbs(&add_limbs(&a0, a1), &add_limbs(&b0, b1));
+    let z1 = sub_limbs(&sub_limbs(&z1, &z0), &z2);
+
+    add_at(&mut ret, &z0, 0);
+    add_at(&mut ret, &z1, half);
+    add_at(&mut ret, &z2, 2 * half);
+    trim(&mut ret);
+    ret
+}
+
+fn limbs_to_u128(a: &[u32]) -> u128 {
+    a.iter()
+        .rev()
+        .fold(0, |acc, &x| acc * BASE as u128 + x as u128)
+}
+
+fn u128_to_limbs(mut x: u128) -> Vec<u32> {
+    let mut ret = Vec::new();
+
+    while x > 0 {
+        ret.push((x % BASE as u128) as u32);
+        x /= BASE as u128;
+    }
+
+    ret
+}
+
+// Reference: Knuth, The Art of Computer Programming Vol. 2, Algorithm 4.3.1 D
+// O(len(a) * len(b)), b must have at least 2 limbs and must not exceed a
+fn div_rem_schoolbook(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let n = b.len();
+    let m = a.len() - n;
+
+    // Scaling leaves the quotient as is and lifts the top limb of the divisor to BASE / 2
+    let f = (BASE / (b[n - 1] as u64 + 1)) as u32;
+    let mut u = mul_small(a, f);
+    u.resize(a.len() + 1, 0);
+    let v = mul_small(b, f);
+    let (v1, v2) = (v[n - 1] as u64, v[n - 2] as u64);
+    let mut q = vec![0; m + 1];
+
+    for j in (0..=m).rev() {
+        let num = u[j + n] as u64 * BASE + u[j + n - 1] as u64;
+        let mut qhat = num / v1;
+        let mut rhat = num % v1;
+
+        while qhat >= BASE || qhat * v2 > rhat * BASE + u[j + n - 2] as u64 {
+            qhat -= 1;
+            rhat += v1;
+
+            if rhat >= BASE {
+                break;
+            }
+        }
+
+        let mut carry = 0;
+        let mut borrow = 0;
+
+        for i in 0..n {
+            let p = qhat * v[i] as u64 + carry;
+            let cur = u[i + j] as i64 - (p % BASE) as i64 - borrow;
+            carry = p / BASE;
+            borrow = (cur < 0) as i64;
+            u[i + j] = (cur + borrow * BASE as i64) as u32;
+        }
+
+        let cur = u[j + n] as i64 - carry as i64 - borrow;
+        u[j + n] = (cur + (cur < 0) as i64 * BASE as i64) as u32;
+
+        // qhat was one too large, so v is added back and the final carry cancels the borrow
+        if cur < 0 {
+            qhat -= 1;
+
+            let mut carry = 0;
+
+            for i in 0..n {
+                let sum = u[i + j] as u64 + v[i] as u64 + carry;
+                u[i + j] = (sum % BASE) as u32;
+                carry = sum / BASE;
+            }
+
+            u[j + n] = ((u[j + n] as u64 + carry) % BASE) as u32;
+        }
+
+        q[j] = qhat as u32;
+    }
+
+    trim(&mut q);
+    (q, div_rem_small(&trimmed(&u[..n]), f).0)
+}
+
+// Reference: Brent and Zimmermann, Modern Computer Arithmetic, Algorithm 3.5
+// For n limbs with the top one at least BASE / 2, returns X with A * X < BASE^2n <= A * (X + 2)
+fn approximate_reciprocal(a: &[u32]) -> Vec<u32> {
+    let n = a.len();
+
+    if n <= NEWTON_THRESHOLD {
+        return div_rem_schoolbook(&vec![BASE as u32 - 1; 2 * n], a).0;
+    }
+
+    let l = (n - 1) / 2;
+    let h = n - l;
+    let mut xh = approximate_reciprocal(&a[l..]);
+    let mut t = mul_limbs(a, &xh);
+
+    let mut bound = vec![0; n + h + 1];
+    bound[n + h] = 1;
+
+    while cmp_limbs(&t, &bound) != Ordering::Less {
+        xh = sub_limbs(&xh, &[1]);
+        t = sub_limbs(&t, a);
+    }
+
+    let t = sub_limbs(&bound, &t);
+    let u = mul_limbs(&t[l.min(t.len())..], &xh);
+
+    let mut ret = vec![0; l];
+    ret.extend_from_slice(&xh);
+
+    if u.len() > 2 * h - l {
+        ret = add_limbs(&ret, &u[2 * h - l..]);
+    }
+
+    ret
+}
+
+fn div_rem_limbs(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    assert!(!b.is_empty(), "Division by zero");
+
+    if cmp_limbs(a, b) == Ordering::Less {
+        return (Vec::new(), a.to_vec());
+    }
+
+    if b.len() == 1 {
+        let (q, r) = div_rem_small(a, b[0]);
+        return (q, if r == 0 { Vec::new() } else { vec![r] });
+    }
+
+    if b.len() <= NEWTON_THRESHOLD || a.len() - b.len() <= NEWTON_THRESHOLD {
+        return div_rem_schoolbook(a, b);
+    }
+
+    let f = (BASE / (*b.last().unwrap() as u64 + 1)) as u32;
+    let mut aa = mul_small(a, f);
+    let mut bb = mul_small(b, f);
+
+    // The reciprocal covers dividends of up to twice the divisor length
+    if aa.len() > 2 * bb.len() {
+        let shift = vec![0; aa.len() - 2 * bb.len()];
+        aa = [shift.clone(), aa].concat();
+        bb = [shift, bb].concat();
+    }
+
+    let n = bb.len();
+    let prod = mul_limbs(&aa, &approximate_reciprocal(&bb));
+
+    // The estimate is at most 3 below the real quotient
+    let mut q = if prod.len() > 2 * n {
+        prod[2 * n..].to_vec()
+    } else {
+        Vec::new()
+    };
+    let mut r = sub_limbs(a, &mul_limbs(&q, b));
+
+    while cmp_limbs(&r, b) != Ordering::Less {
+        r = sub_limbs(&r, b);
+        q = add_limbs(&q, &[1]);
+    }
+
+    (q, r)
+}
+
+fn isqrt_u128(n: u128) -> u128 {
+    let mut ret = (n as f64).sqrt() as u128;
+
+    while ret * ret > n {
+        ret -= 1;
+    }
+
+    while (ret + 1) * (ret + 1) <= n {
+        ret += 1;
+    }
+
+    ret
+}
+
+fn sqrt_limbs(a: &[u32]) -> Vec<u32> {
+    if a.len() <= 4 {
+        return u128_to_limbs(isqrt_u128(limbs_to_u128(a)));
+    }
+
+    // The root of the top limbs is off by less than BASE^k from above,
+    // so a single Newton step is within one of the answer
+    let k = (a.len() - 1) / 4;
+    let mut x = vec![0; k];
+    x.extend(add_limbs(&sqrt_limbs(&a[2 * k..]), &[1]));
+    x = div_rem_small(&add_limbs(&x, &div_rem_limbs(a, &x).0), 2).0;
+
+    while cmp_limbs(&mul_limbs(&x, &x), a) == Ordering::Greater {
+        x = sub_limbs(&x, &[1]);
+    }
+
+    x
+}
+
+#[derive(Debug)]
+struct ParseBigIntError;
+
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    fn new() -> Self {
+        Self { limbs: Vec::new() }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        let ret = limbs_to_u128(self.limbs.get(..3).unwrap_or(&self.limbs));
+
+        if self.limbs.len() <= 3 && ret <= u64::MAX as u128 {
+            Some(ret as u64)
+        } else {
+            None
+        }
+    }
+
+    fn div_rem(&self, other: &Self) -> (Self, Self) {
+        let (q, r) = div_rem_limbs(&self.limbs, &other.limbs);
+        (Self { limbs: q }, Self { limbs: r })
+    }
+
+    // Floor of the square root
+    fn sqrt(&self) -> Self {
+        Self {
+            limbs: sqrt_limbs(&self.limbs),
+        }
+    }
+
+    fn pow(&self, mut exp: u32) -> Self {
+        let mut ret = Self::from(1);
+        let mut piv = self.clone();
+
+        while exp > 0 {
+            if exp & 1 != 0 {
+                ret = &ret * &piv;
+            }
+
+            exp >>= 1;
+
+            if exp > 0 {
+                piv = &piv * &piv;
+            }
+        }
+
+        ret
+    }
+
+    fn pow_mod_u64(&self, mut exp: u64, modular: &Self) -> Self {
+        let mut ret = &Self::from(1) % modular;
+        let mut piv = self % modular;
+
+        while exp > 0 {
+            if exp & 1 != 0 {
+                ret = &(&ret * &piv) % modular;
+            }
+
+            piv = &(&piv * &piv) % modular;
+            exp >>= 1;
+        }
+
+        ret
+    }
+
+    // The exponent is consumed one limb at a time from the top
+    fn modpow(&self, exp: &Self, modular: &Self) -> Self {
+        let base = self % modular;
+        let mut ret = &Self::from(1) % modular;
+
+        for &limb in exp.limbs.iter().rev() {
+            ret = ret.pow_mod_u64(BASE, modular);
+            ret = &(&ret * &base.pow_mod_u64(limb as u64, modular)) % modular;
+        }
+
+        ret
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(val: u64) -> Self {
+        Self {
+            limbs: u128_to_limbs(val as u128),
+        }
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_limbs(&self.limbs, &other.limbs)
+    }
+}
+
+impl std::ops::Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        BigUint {
+            limbs: add_limbs(&self.limbs, &other.limbs),
+        }
+    }
+}
+
+impl std::ops::Sub for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: &BigUint) -> BigUint {
+        assert!(*self >= *other, "Subtraction underflow");
+
+        BigUint {
+            limbs: sub_limbs(&self.limbs, &other.limbs),
+        }
+    }
+}
+
+impl std::ops::Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        BigUint {
+            limbs: mul_limbs(&self.limbs, &other.limbs),
+        }
+    }
+}
+
+impl std::ops::Div for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).0
+    }
+}
+
+impl std::ops::Rem for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).1
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix('+').unwrap_or(s);
+
+        if s.is_empty() || !s.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(ParseBigIntError);
+        }
+
+        let mut limbs = s
+            .as_bytes()
+            .rchunks(BASE_DIGITS)
+            .map(|chunk| chunk.iter().fold(0, |acc, &c| acc * 10 + (c - b'0') as u32))
+            .collect::<Vec<_>>();
+        trim(&mut limbs);
+
+        Ok(Self { limbs })
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut ret = self.limbs.last().map_or("0".to_string(), |x| x.to_string());
+
+        for limb in self.limbs.iter().rev().skip(1) {
+            ret += &format!("{limb:09}");
+        }
+
+        f.pad_integral(true, "", &ret)
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
+// Sign and magnitude, zero is never negative
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+struct BigInt {
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    fn from_parts(negative: bool, magnitude: BigUint) -> Self {
+        Self {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    fn abs(&self) -> Self {
+        Self::from_parts(false, self.magnitude.clone())
+    }
+
+    fn pow(&self, exp: u32) -> Self {
+        Self::from_parts(self.negative && exp & 1 != 0, self.magnitude.pow(exp))
+    }
+
+    // Remainder in [0, modular)
+    fn rem_euclid(&self, modular: &BigUint) -> BigUint {
+        let ret = &self.magnitude % modular;
+
+        if self.negative && !ret.is_zero() {
+            modular - &ret
+        } else {
+            ret
+        }
+    }
+
+    fn modpow(&self, exp: &BigUint, modular: &BigUint) -> BigUint {
+        self.rem_euclid(modular).modpow(exp, modular)
+    }
+
+    fn sqrt(&self) -> Self {
+        assert!(!self.negative, "Square root of a negative number");
+        Self::from_parts(false, self.magnitude.sqrt())
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(val: i64) -> Self {
+        Self::from_parts(val < 0, BigUint::from(val.unsigned_abs()))
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(val: BigUint) -> Self {
+        Self::from_parts(false, val)
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl std::ops::Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl std::ops::Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+impl std::ops::Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, &self.magnitude + &other.magnitude);
+        }
+
+        if self.magnitude >= other.magnitude {
+            BigInt::from_parts(self.negative, &self.magnitude - &other.magnitude)
+        } else {
+            BigInt::from_parts(other.negative, &other.magnitude - &self.magnitude)
+        }
+    }
+}
+
+impl std::ops::Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl std::ops::Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(
+            self.negative != other.negative,
+            &self.magnitude * &other.magnitude,
+        )
+    }
+}
+
+// Rounds toward zero like the primitive integers
+impl std::ops::Div for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(
+            self.negative != other.negative,
+            &self.magnitude / &other.magnitude,
+        )
+    }
+}
+
+// Takes the sign of the dividend like the primitive integers
+impl std::ops::Rem for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(self.negative, &self.magnitude % &other.magnitude)
+    }
+}
+
+// Owned operands and compound assignment forward to the reference implementations
+macro_rules! impl_big_ops {
+    ($t:ty, $($trait:ident $method:ident $assign_trait:ident $assign_method:ident),*) => {$(
+        impl std::ops::$trait for $t {
+            type Output = $t;
+
+            fn $method(self, other: $t) -> $t {
+                std::ops::$trait::$method(&self, &other)
+            }
+        }
+
+        impl std::ops::$assign_trait for $t {
+            fn $assign_method(&mut self, other: $t) {
+                *self = std::ops::$trait::$method(&*self, &other);
+            }
+        }
+    )*};
+}
+
+impl_big_ops!(
+    BigUint, Add add AddAssign add_assign, Sub sub SubAssign sub_assign,
+    Mul mul MulAssign mul_assign, Div div DivAssign div_assign, Rem rem RemAssign rem_assign
+);
+impl_big_ops!(
+    BigInt, Add add AddAssign add_assign, Sub sub SubAssign sub_assign,
+    Mul mul MulAssign mul_assign, Div div DivAssign div_assign, Rem rem RemAssign rem_assign
+);
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('-') {
+            Some(rest) if rest.starts_with('+') => Err(ParseBigIntError),
+            Some(rest) => Ok(Self::from_parts(true, rest.parse()?)),
+            None => Ok(Self::from_parts(false, s.parse()?)),
+        }
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_string())
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{self}")
+    }
+}
@@ -0,0 +1,709 @@
+use std::collections::BTreeMap;
+
+// Every structure keeps the maximum, minimum versions store negated lines
+trait LineNum:
+    Copy
+    + Ord
+    + From<i32>
+    + std::ops::Add<Output = Self>
+    + std::ops::Sub<Output = Self>
+    + std::ops::Mul<Output = Self>
+    + std::ops::Div<Output = Self>
+    + std::ops::Rem<Output = Self>
+    + std::ops::Neg<Output = Self>
+{
+    const MIN: Self;
+    const MAX: Self;
+}
+
+impl LineNum for i64 {
+    const MIN: Self = i64::MIN;
+    const MAX: Self = i64::MAX;
+}
+
+impl LineNum for i128 {
+    const MIN: Self = i128::MIN;
+    const MAX: Self = i128::MAX;
+}
+
+fn floor_div<T: LineNum>(a: T, b: T) -> T {
+    let zero = T::from(0);
+    let q = a / b;
+
+    if a % b != zero && ((a < zero) != (b < zero)) {
+        q - T::from(1)
+    } else {
+        q
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Line<T: LineNum> {
+    a: T,
+    b: T,
+}
+
+impl<T: LineNum> Line<T> {
+    fn new(a: T, b: T) -> Self {
+        Self { a, b }
+    }
+
+    fn eval(&self, x: T) -> T {
+        self.a * x + self.b
+    }
+
+    fn signed(self, minimize: bool) -> Self {
+        if minimize {
+            Self::new(-self.a, -self.b)
+        } else {
+            self
+        }
+    }
+
+    // Last integer x with self(x) >= other(x), self.a must be below other.a
+    fn cross(&self, other: &Self) -> T {
+        floor_div(self.b - other.b, other.a - self.a)
+    }
+}
+
+// Lines arrive with increasing slopes for a maximum, decreasing slopes for a minimum
+struct MonotoneCHT<T: LineNum> {
+    minimize: bool,
+    lines: Vec<Line<T>>,
+    head: usize,
+}
+
+impl<T: LineNum> MonotoneCHT<T> {
+    fn new(minimize: bool) -> Self {
+        Self {
+            minimize,
+            lines: Vec::new(),
+            head: 0,
+        }
+    }
+
+    fn add(&mut self, line: Line<T>) {
+        let line = line.signed(self.minimize);
+
+        if let Some(last) = self.lines.last() {
+            assert!(last.a <= line.a, "Slopes must arrive in order");
+
+            if last.a == line.a {
+                if last.b >= line.b {
+                    return;
+                }
+
+                self.lines.pop();
+            }
+        }
+
+        while self.lines.len() >= 2 {
+            let len = self.lines.len();
+
+            if self.lines[len - 2].cross(&self.lines[len - 1]) < self.lines[len - 1].cross(&line) {
+                break;
+            }
+
+            self.lines.pop();
+        }
+
+        // Clamping before the push keeps head on the surviving lines that are not yet passed
+        self.head = self.head.min(self.lines.len().saturating_sub(1));
+        self.lines.push(line);
+    }
+
+    fn unsigned(&self, val: T) -> T {
+        if self.minimize {
+            -val
+        } else {
+            val
+        }
+    }
+
+    // Binary search over the hull in O(log n)
+    fn query(&self, x: T) -> Option<T> {
+        if self.lines.is_empty() {
+            return None;
+        }
+
+        let mut left = 0;
+        let mut right = self.lines.len() - 1;
+
+        while left < right {
+            let mid = (left + right) / 2;
+
+            if self.lines[mid].eval(x) < self.lines[mid + 1].eval(x) {
+                left = mid + 1;
+            } else {
+                right = mid;
+            }
+        }
+
+        Some(self.unsigned(self.lines[left].eval(x)))
+    }
+
+    // Amortized O(1) when x never decreases between calls
+    fn query_monotone(&mut self, x: T) -> Option<T> {
+        if self.lines.is_empty() {
+            return None;
+        }
+
+        while self.head + 1 < self.lines.len()
+            && self.lines[self.head].eval(x) <= self.lines[self.head + 1].eval(x)
+        {
+            self.head += 1;
+        }
+
+        Some(self.unsigned(self.lines[self.head].eval(x)))
+    }
+}
+
+// Reference: https://github.com/kth-competitive-programming/kactl/blob/main/content/data-structures/LineContainer.h
+// Lines in any order, each line keeps p, the last x where it is optimal
+// lines maps slope to (intercept, p) and breaks orders the same lines by p
+struct LineContainer<T: LineNum> {
+    minimize: bool,
+    lines: BTreeMap<T, (T, T)>,
+    breaks: BTreeMap<(T, T), ()>,
+}
+
+impl<T: LineNum> LineContainer<T> {
+    fn new(minimize: bool) -> Self {
+        Self {
+            minimize,
+            lines: BTreeMap::new(),
+            breaks: BTreeMap::new(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    fn prev(&self, slope: T) -> Option<T> {
+        self.lines.range(..slope).next_back().map(|(&a, _)| a)
+    }
+
+    fn next(&self, slope: T) -> Option<T> {
+        self.lines.range(slope..).nth(1).map(|(&a, _)| a)
+    }
+
+    fn remove(&mut self, slope: T) {
+        let (_, p) = self.lines.remove(&slope).unwrap();
+        self.breaks.remove(&(p, slope));
+    }
+
+    fn set_p(&mut self, slope: T, p: T) {
+        let entry = self.lines.get_mut(&slope).unwrap();
+
+        self.breaks.remove(&(entry.1, slope));
+        self.breaks.insert((p, slope), ());
+        entry.1 = p;
+    }
+
+    // Recomputes p of x against its successor y, true if y makes x useless
+    fn intersect(&mut self, x: T, y: Option<T>) -> bool {
+        let y = match y {
+            Some(y) => y,
+            None => {
+                self.set_p(x, T::MAX);
+                return false;
+            }
+        };
+
+        let (xb, _) = self.lines[&x];
+        let (yb, yp) = self.lines[&y];
+        let p = Line::new(x, xb).cross(&Line::new(y, yb));
+
+        self.set_p(x, p);
+        p >= yp
+    }
+
+    fn add(&mut self, line: Line<T>) {
+        let Line { a, b } = line.signed(self.minimize);
+
+        if let Some(&(old, _)) = self.lines.get(&a) {
+            if old >= b {
+                return;
+            }
+
+            self.remove(a);
+        }
+
+        self.lines.insert(a, (b, T::MIN));
+        self.breaks.insert((T::MIN, a), ());
+
+        while let Some(z) = self.next(a) {
+            if !self.intersect(a, Some(z)) {
+                break;
+            }
+
+            self.remove(z);
+        }
+
+        if self.next(a).is_none() {
+            self.intersect(a, None);
+        }
+
+        let mut y = match self.prev(a) {
+            Some(x) => {
+                if self.intersect(x, Some(a)) {
+                    self.remove(a);
+                    let next = self.next(x);
+                    self.intersect(x, next);
+                }
+
+                x
+            }
+            None => return,
+        };
+
+        while let Some(x) = self.prev(y) {
+            if self.lines[&x].1 < self.lines[&y].1 {
+                break;
+            }
+
+            self.remove(y);
+            let next = self.next(x);
+            self.intersect(x, next);
+            y = x;
+        }
+    }
+
+    fn query(&self, x: T) -> Option<T> {
+        let (_, a) = *self.breaks.range((x, T::MIN)..).next()?.0;
+        let val = a * x + self.lines[&a].0;
+
+        Some(if self.minimize { -val } else { val })
+    }
+}
+
+#[derive(Clone)]
+struct LiChaoNode<T: LineNum> {
+    line: Option<Line<T>>,
+    left: usize,
+    right: usize,
+}
+
+// Reference: https://cp-algorithms.com/geometry/convex_hull_trick.html#li-chao-tree
+// Integer x in [lo, hi] with nodes created on demand, node 0 stands for an empty subtree
+// A persistent tree copies every touched node and keeps one root per version
+struct LiChaoTree<T: LineNum> {
+    lo: T,
+    hi: T,
+    minimize: bool,
+    persistent: bool,
+    nodes: Vec<LiChaoNode<T>>,
+    roots: Vec<usize>,
+}
+
+impl<T: LineNum> LiChaoTree<T> {
+    fn new(lo: T, hi: T, minimize: bool, persistent: bool) -> Self {
+        Self {
+            lo,
+            hi,
+            minimize,
+            persistent,
+            nodes: vec![LiChaoNode {
+                line: None,
+                left: 0,
+                right: 0,
+            }],
+            roots: vec![0],
+        }
+    }
+
+    // Version 0 is empty and every insertion into a persistent tree adds one
+    fn latest(&self) -> usize {
+        self.roots.len() - 1
+    }
+
+    fn touch(&mut self, node: usize) -> usize {
+        if node != 0 && !self.persistent {
+            return node;
+        }
+
+        self.nodes.push(self.nodes[node].clone());
+        self.nodes.len() - 1
+    }
+
+    fn process_add_line(&mut self, node: usize, start: T, end: T, mut line: Line<T>) -> usize {
+        let node = self.touch(node);
+        let mut cur = match self.nodes[node].line {
+            Some(cur) => cur,
+            None => {
+                self.nodes[node].line = Some(line);
+                return node;
+            }
+        };
+
+        let mid = start + (end - start) / T::from(2);
+
+        // The node keeps whichever line wins at mid, the loser can only win on one side
+        if line.eval(mid) > cur.eval(mid) {
+            std::mem::swap(&mut line, &mut cur);
+            self.nodes[node].line = Some(cur);
+        }
+
+        if start == end {
+            return node;
+        }
+
+        if line.eval(start) > cur.eval(start) {
+            let left = self.process_add_line(self.nodes[node].left, start, mid, line);
+            self.nodes[node].left = left;
+        } else if line.eval(end) > cur.eval(end) {
+            let right = self.process_add_line(self.nodes[node].right, mid + T::from(1), end, line);
+            self.nodes[node].right = right;
+        }
+
+        node
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn process_add_segment(
+        &mut self,
+        node: usize,
+        start: T,
+        end: T,
+        left: T,
+        right: T,
+        line: Line<T>,
+    ) -> usize {
+        if right < start || end < left {
+            return node;
+        }
+
+        if left <= start && end <= right {
+            return self.process_add_line(node, start, end, line);
+        }
+
+        let node = self.touch(node);
+        let mid = start + (end - start) / T::from(2);
+        let child = self.process_add_segment(self.nodes[node].left, start, mid, left, right, line);
+        self.nodes[node].left = child;
+        let child = self.process_add_segment(
+            self.nodes[node].right,
+            mid + T::from(1),
+            end,
+            left,
+            right,
+            line,
+        );
+        self.nodes[node].right = child;
+
+        node
+    }
+
+    fn commit(&mut self, root: usize) {
+        if self.persistent {
+            self.roots.push(root);
+        } else {
+            self.roots[0] = root;
+        }
+    }
+
+    // O(log C)
+    fn add_line(&mut self, line: Line<T>) {
+        let root = self.roots[self.latest()];
+        let root = self.process_add_line(root, self.lo, self.hi, line.signed(self.minimize));
+        self.commit(root);
+    }
+
+    // The line only counts for x in [left, right], O(log^2 C)
+    fn add_segment(&mut self, line: Line<T>, left: T, right: T) {
+        let root = self.roots[self.latest()];
+        let root = self.process_add_segment(
+            root,
+            self.lo,
+            self.hi,
+            left,
+            right,
+            line.signed(self.minimize),
+        );
+        self.commit(root);
+    }
+
+    fn query_version(&self, version: usize, x: T) -> Option<T> {
+        let mut node = self.roots[version];
+        let (mut start, mut end) = (self.lo, self.hi);
+        let mut ret: Option<T> = None;
+
+        while node != 0 {
+            if let Some(line) = self.nodes[node].line {
+                let val = line.eval(x);
+                ret = Some(ret.map_or(val, |r| r.max(val)));
+            }
+
+            let mid = start + (end - start) / T::from(2);
+
+            if x <= mid {
+                node = self.nodes[node].left;
+                end = mid;
+            } else {
+                node = self.nodes[node].right;
+                start = mid + T::from(1);
+            }
+        }
+
+        ret.map(|val| if self.minimize { -val } else { val })
+    }
+
+    fn query(&self, x: T) -> Option<T> {
+        self.query_version(self.latest(), x)
+    }
+}
+
+// Reference: https://koosaga.com/307
+// Maximum of a[i] * t + b[i] over ranges while heaten moves t forward on ranges of lines
+// For a minimum, negate both parameters of every line and the answers
+struct KineticSegmentTree<T: LineNum> {
+    size: usize,
+    data: Vec<Line<T>>,
+    melt: Vec<T>,
+    lazy_time: Vec<T>,
+    lazy_add: Vec<T>,
+}
+
+impl<T: LineNum> KineticSegmentTree<T> {
+    fn new(lines: &[Line<T>]) -> Self {
+        let size = lines.len().max(1);
+        let zero = T::from(0);
+        let mut ret = Self {
+            size,
+            data: vec![Line::new(zero, T::MIN); size * 4],
+            melt: vec![T::MAX; size * 4],
+            lazy_time: vec![zero; size * 4],
+            lazy_add: vec![zero; size * 4],
+        };
+
+        if !lines.is_empty() {
+            ret.construct(lines, 1, 0, size - 1);
+        }
+
+        ret
+    }
+
+    fn construct(&mut self, lines: &[Line<T>], node: usize, start: usize, end: usize) {
+        if start == end {
+            self.data[node] = lines[start];
+            return;
+        }
+
+        let mid = (start + end) / 2;
+
+        self.construct(lines, node * 2, start, mid);
+        self.construct(lines, node * 2 + 1, mid + 1, end);
+        self.merge(node);
+    }
+
+    // Ties go to the larger slope, so the loser can only overtake after a positive time
+    fn merge(&mut self, node: usize) {
+        let (left, right) = (self.data[node * 2], self.data[node * 2 + 1]);
+        let (winner, loser) = if (left.b, left.a) >= (right.b, right.a) {
+            (left, right)
+        } else {
+            (right, left)
+        };
+
+        let overtake = if loser.a > winner.a {
+            let (num, den) = (winner.b - loser.b, loser.a - winner.a);
+            -floor_div(-num, den)
+        } else {
+            T::MAX
+        };
+
+        self.data[node] = winner;
+        self.melt[node] = overtake
+            .min(self.melt[node * 2])
+            .min(self.melt[node * 2 + 1]);
+    }
+
+    fn apply(&mut self, node: usize, time: T, add: T) {
+        let line = &mut self.data[node];
+
+        line.b = line.b + line.a * time + add;
+
+        if self.melt[node] != T::MAX {
+            self.melt[node] = self.melt[node] - time;
+        }
+
+        self.lazy_time[node] = self.lazy_time[node] + time;
+        self.lazy_add[node] = self.lazy_add[node] + add;
+    }
+
+    fn propagate(&mut self, node: usize) {
+        let zero = T::from(0);
+        let (time, add) = (self.lazy_time[node], self.lazy_add[node]);
+
+        if time == zero && add == zero {
+            return;
+        }
+
+        self.apply(node * 2, time, add);
+        self.apply(node * 2 + 1, time, add);
+        self.lazy_time[node] = zero;
+        self.lazy_add[node] = zero;
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn process_update(
+        &mut self,
+        node: usize,
+        start: usize,
+        end: usize,
+        left: usize,
+        right: usize,
+        time: T,
+        add: T,
+    ) {
+        if right < start || end < left {
+            return;
+        }
+
+        // A whole node moves lazily unless one of its winners changes on the way
+        if left <= start && end <= right && (start == end || time < self.melt[node]) {
+            self.apply(node, time, add);
+            return;
+        }
+
+        self.propagate(node);
+
+        let mid = (start + end) / 2;
+
+        self.process_update(node * 2, start, mid, left, right, time, add);
+        self.process_update(node * 2 + 1, mid + 1, end, left, right, time, add);
+        self.merge(node);
+    }
+
+    // b[i] += a[i] * time for every i in [left, right], time must not be negative
+    fn heaten(&mut self, left: usize, right: usize, time: T) {
+        assert!(time >= T::from(0), "Time only moves forward");
+        self.process_update(1, 0, self.size - 1, left, right, time, T::from(0));
+    }
+
+    // b[i] += val for every i in [left, right]
+    fn add(&mut self, left: usize, right: usize, val: T) {
+        self.process_update(1, 0, self.size - 1, left, right, T::from(0), val);
+    }
+
+    fn process_set(&mut self, node: usize, start: usize, end: usize, idx: usize, line: Line<T>) {
+        if start == end {
+            self.data[node] = line;
+            return;
+        }
+
+        self.propagate(node);
+
+        let mid = (start + end) / 2;
+
+        if idx <= mid {
+            self.process_set(node * 2, start, mid, idx, line);
+        } else {
+            self.process_set(node * 2 + 1, mid + 1, end, idx, line);
+        }
+
+        self.merge(node);
+    }
+
+    fn set(&mut self, idx: usize, line: Line<T>) {
+        self.process_set(1, 0, self.size - 1, idx, line);
+    }
+
+    fn process_query(
+        &mut self,
+        node: usize,
+        start: usize,
+        end: usize,
+        left: usize,
+        right: usize,
+    ) -> T {
+        if right < start || end < left {
+            return T::MIN;
+        }
+
+        if left <= start && end <= right {
+            return self.data[node].b;
+        }
+
+        self.propagate(node);
+
+        let mid = (start + end) / 2;
+
+        self.process_query(node * 2, start, mid, left, right)
+            .max(self.process_query(node * 2 + 1, mid + 1, end, left, right))
+    }
+
+    // Current maximum over [left, right]
+    fn query(&mut self, left: usize, right: usize) -> T {
+        self.process_query(1, 0, self.size - 1, left, right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self, n: u64) -> u64 {
+            self.0 ^= self.0 << 7;
+            self.0 ^= self.0 >> 9;
+            self.0 % n
+        }
+    }
+
+    #[test]
+    fn monotone_cht_keeps_head_on_the_hull() {
+        let mut cht = MonotoneCHT::<i64>::new(false);
+
+        for (a, b) in [(-3, 9), (-2, 7), (-2, 9), (-1, 0), (-1, 5), (-1, 2)] {
+            cht.add(Line::new(a, b));
+        }
+
+        for x in -3..=0 {
+            cht.query_monotone(x);
+        }
+
+        cht.add(Line::new(0, 8));
+        assert_eq!(cht.query_monotone(0), Some(9));
+    }
+
+    #[test]
+    fn monotone_cht_matches_brute_force() {
+        let mut rng = Rng(88172645463325252);
+
+        for _ in 0..500 {
+            let minimize = rng.next(2) == 0;
+            let mut cht = MonotoneCHT::new(minimize);
+            let mut lines = Vec::new();
+            let mut a = -10i64;
+            let mut x = -10i64;
+
+            for _ in 0..30 {
+                if lines.is_empty() || rng.next(2) == 0 {
+                    a += rng.next(3) as i64;
+
+                    let slope = if minimize { -a } else { a };
+                    let b = rng.next(21) as i64 - 10;
+
+                    cht.add(Line::new(slope, b));
+                    lines.push((slope, b));
+                } else {
+                    x += rng.next(3) as i64;
+
+                    let vals = lines.iter().map(|&(a, b)| a * x + b);
+                    let best = if minimize { vals.min() } else { vals.max() };
+
+                    assert_eq!(cht.query(x), best);
+                    assert_eq!(cht.query_monotone(x), best);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,475 @@
+use std::cell::RefCell;
+use std::f64::consts::PI;
+use std::ops::{Add, Mul, Sub};
+
+const MOD: u32 = 998_244_353;
+
+// Squares in u64, so modular must be below 2^32
+fn pow_mod(mut base: u64, mut exp: u64, modular: u64) -> u64 {
+    let mut ret = 1;
+    base %= modular;
+
+    while exp > 0 {
+        if exp % 2 == 1 {
+            ret = ret * base % modular;
+        }
+
+        base = base * base % modular;
+        exp /= 2;
+    }
+
+    ret
+}
+
+fn primitive_root(modular: u32) -> u32 {
+    let modular = modular as u64;
+    let mut factors = Vec::new();
+    let mut rest = modular - 1;
+    let mut p = 2;
+
+    while p * p <= rest {
+        if rest.is_multiple_of(p) {
+            factors.push(p);
+
+            while rest.is_multiple_of(p) {
+                rest /= p;
+            }
+        }
+
+        p += 1;
+    }
+
+    if rest > 1 {
+        factors.push(rest);
+    }
+
+    (2..)
+        .find(|&g| {
+            factors
+                .iter()
+                .all(|&q| pow_mod(g, (modular - 1) / q, modular) != 1)
+        })
+        .unwrap() as u32
+}
+
+thread_local! {
+    static NTT_ROOTS: RefCell<Vec<(u32, u32)>> = const { RefCell::new(Vec::new()) };
+}
+
+// Primitive root of modular, factored once per modulus and cached afterwards
+fn ntt_root(modular: u32) -> u32 {
+    NTT_ROOTS.with(|roots| {
+        let mut roots = roots.borrow_mut();
+
+        if let Some(&(_, g)) = roots.iter().find(|&&(m, _)| m == modular) {
+            return g;
+        }
+
+        let g = primitive_root(modular);
+        roots.push((modular, g));
+
+        g
+    })
+}
+
+// Iterative in-place NTT of length 2^k, modular must be a prime below 2^31 with 2^k | modular - 1
+fn process_ntt(arr: &mut [u32], is_reverse: bool, modular: u32) {
+    let n = arr.len();
+    let m = modular as u64;
+    let mut j = 0;
+
+    for i in 1..n {
+        let mut bit = n >> 1;
+
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+
+        j ^= bit;
+
+        if i < j {
+            arr.swap(i, j);
+        }
+    }
+
+    let g = ntt_root(modular) as u64;
+    let mut roots = Vec::with_capacity(n / 2);
+    let mut len = 2;
+
+    while len <= n {
+        let mut w = pow_mod(g, (m - 1) / len as u64, m);
+
+        if is_reverse {
+            w = pow_mod(w, m - 2, m);
+        }
+
+        let half = len / 2;
+        let mut cur = 1;
+        roots.clear();
+
+        // Each root keeps floor(root * 2^32 / m), so the butterflies need no division
+        for _ in 0..half {
+            roots.push((cur as u32, ((cur << 32) / m) as u32));
+            cur = cur * w % m;
+        }
+
+        for chunk in arr.chunks_exact_mut(len) {
+            let (low, high) = chunk.split_at_mut(half);
+
+            for ((x, y), &(root, quot)) in low.iter_mut().zip(high.iter_mut()).zip(roots.iter()) {
+                let q = (*y as u64 * quot as u64) >> 32;
+                let v = (*y as u64 * root as u64).wrapping_sub(q * m) as u32;
+                let v = if v >= modular { v - modular } else { v };
+                let u = *x;
+
+                *x = if u + v >= modular {
+                    u + v - modular
+                } else {
+                    u + v
+                };
+                *y = if u >= v { u - v } else { u + modular - v };
+            }
+        }
+
+        len <<= 1;
+    }
+
+    if is_reverse {
+        let inv_n = pow_mod(n as u64, m - 2, m);
+
+        for val in arr.iter_mut() {
+            *val = (*val as u64 * inv_n % m) as u32;
+        }
+    }
+}
+
+fn convolution_naive(a: &[u32], b: &[u32], modular: u32) -> Vec<u32> {
+    let m = modular as u64;
+    let mut ret = vec![0; a.len() + b.len() - 1];
+
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            ret[i + j] = ((ret[i + j] as u64 + x as u64 * y as u64) % m) as u32;
+        }
+    }
+
+    ret
+}
+
+// Values of a and b must be below modular, which must be NTT-friendly
+fn convolution_mod(a: &[u32], b: &[u32], modular: u32) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    if a.len().min(b.len()) <= 60 {
+        return convolution_naive(a, b, modular);
+    }
+
+    let len = a.len() + b.len() - 1;
+    let n = len.next_power_of_two();
+    let m = modular as u64;
+    let mut fa = a.to_vec();
+    let mut fb = b.to_vec();
+
+    fa.resize(n, 0);
+    fb.resize(n, 0);
+
+    process_ntt(&mut fa, false, modular);
+    process_ntt(&mut fb, false, modular);
+
+    for i in 0..n {
+        fa[i] = (fa[i] as u64 * fb[i] as u64 % m) as u32;
+    }
+
+    process_ntt(&mut fa, true, modular);
+    fa.truncate(len);
+
+    fa
+}
+
+fn convolution(a: &[u32], b: &[u32]) -> Vec<u32> {
+    convolution_mod(a, b, MOD)
+}
+
+const PRIME1: u64 = 754_974_721;
+const PRIME2: u64 = 167_772_161;
+const PRIME3: u64 = 469_762_049;
+
+// PRIME1^-1 mod PRIME2 and (PRIME1 * PRIME2)^-1 mod PRIME3
+const INV_P1_MOD_P2: u64 = 95_869_806;
+const INV_P1P2_MOD_P3: u64 = 187_290_749;
+
+// Garner's algorithm, the value below PRIME1 * PRIME2 * PRIME3 with the given residues
+fn garner(r1: u64, r2: u64, r3: u64) -> u128 {
+    let t2 = (r2 + PRIME2 - r1 % PRIME2) % PRIME2 * INV_P1_MOD_P2 % PRIME2;
+    let x12 = r1 + PRIME1 * t2;
+    let t3 = (r3 + PRIME3 - x12 % PRIME3) % PRIME3 * INV_P1P2_MOD_P3 % PRIME3;
+
+    x12 as u128 + (PRIME1 * PRIME2) as u128 * t3 as u128
+}
+
+// Exact convolution by three NTT primes and Garner's algorithm
+// Correct while every coefficient is below PRIME1 * PRIME2 * PRIME3 (about 5.9 * 10^25)
+fn convolution_exact(a: &[u64], b: &[u64]) -> Vec<u128> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    let reduce = |arr: &[u64], p: u64| arr.iter().map(|&x| (x % p) as u32).collect::<Vec<_>>();
+    let c1 = convolution_mod(&reduce(a, PRIME1), &reduce(b, PRIME1), PRIME1 as u32);
+    let c2 = convolution_mod(&reduce(a, PRIME2), &reduce(b, PRIME2), PRIME2 as u32);
+    let c3 = convolution_mod(&reduce(a, PRIME3), &reduce(b, PRIME3), PRIME3 as u32);
+
+    (0..c1.len())
+        .map(|i| garner(c1[i] as u64, c2[i] as u64, c3[i] as u64))
+        .collect()
+}
+
+// Convolution modulo any modular below 2^31 by three NTT primes
+fn convolution_arbitrary_mod(a: &[u32], b: &[u32], modular: u32) -> Vec<u32> {
+    let a = a.iter().map(|&x| x as u64).collect::<Vec<_>>();
+    let b = b.iter().map(|&x| x as u64).collect::<Vec<_>>();
+
+    convolution_exact(&a, &b)
+        .into_iter()
+        .map(|x| (x % modular as u128) as u32)
+        .collect()
+}
+
+#[derive(Default, Clone, Copy)]
+struct Complex {
+    real: f64,
+    imaginary: f64,
+}
+
+impl Complex {
+    fn new(real: f64, imaginary: f64) -> Self {
+        Self { real, imaginary }
+    }
+
+    fn conj(self) -> Self {
+        Self::new(self.real, -self.imaginary)
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Complex::new(self.real + other.real, self.imaginary + other.imaginary)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Complex::new(self.real - other.real, self.imaginary - other.imaginary)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Complex::new(
+            self.real * rhs.real - self.imaginary * rhs.imaginary,
+            self.real * rhs.imaginary + self.imaginary * rhs.real,
+        )
+    }
+}
+
+// Roots are computed directly instead of by repeated multiplication to keep the error small
+fn process_fft(arr: &mut [Complex], is_reverse: bool) {
+    let n = arr.len();
+    let mut j = 0;
+
+    for i in 1..n {
+        let mut bit = n >> 1;
+
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+
+        j ^= bit;
+
+        if i < j {
+            arr.swap(i, j);
+        }
+    }
+
+    // roots[half + k] is the k-th root of the level of length 2 * half, so every level reads
+    // its roots contiguously, and only the top level needs trigonometry
+    let sign = if is_reverse { 1.0 } else { -1.0 };
+    let mut roots = vec![Complex::default(); n.max(2)];
+
+    for k in 0..n / 2 {
+        let angle = sign * 2.0 * PI * k as f64 / n as f64;
+        roots[n / 2 + k] = Complex::new(angle.cos(), angle.sin());
+    }
+
+    for k in (1..n / 2).rev() {
+        roots[k] = roots[2 * k];
+    }
+
+    let mut len = 2;
+
+    while len <= n {
+        let half = len / 2;
+        let level = &roots[half..len];
+
+        for chunk in arr.chunks_exact_mut(len) {
+            let (low, high) = chunk.split_at_mut(half);
+
+            for ((x, y), &w) in low.iter_mut().zip(high.iter_mut()).zip(level.iter()) {
+                let u = *x;
+                let v = *y * w;
+
+                *x = u + v;
+                *y = u - v;
+            }
+        }
+
+        len <<= 1;
+    }
+
+    if is_reverse {
+        for val in arr.iter_mut() {
+            val.real /= n as f64;
+            val.imaginary /= n as f64;
+        }
+    }
+}
+
+// Splits every value in [-2^29, 2^29] into two balanced 15-bit digits and runs four FFTs
+// Returns the exact convolutions (low * low, low * high + high * low, high * high)
+fn convolution_split(a: &[i64], b: &[i64]) -> [Vec<i64>; 3] {
+    let len = a.len() + b.len() - 1;
+    let n = len.next_power_of_two();
+    let split = |x: i64| {
+        let low = ((x + (1 << 14)) & 0x7fff) - (1 << 14);
+        Complex::new(low as f64, ((x - low) >> 15) as f64)
+    };
+    let mut fa = vec![Complex::default(); n];
+    let mut fb = vec![Complex::default(); n];
+
+    for (i, &x) in a.iter().enumerate() {
+        fa[i] = split(x);
+    }
+
+    for (i, &x) in b.iter().enumerate() {
+        fb[i] = split(x);
+    }
+
+    process_fft(&mut fa, false);
+    process_fft(&mut fb, false);
+
+    let mut fx = vec![Complex::default(); n];
+    let mut fy = vec![Complex::default(); n];
+    let half = Complex::new(0.5, 0.0);
+    let half_i = Complex::new(0.0, -0.5);
+    let unit_i = Complex::new(0.0, 1.0);
+
+    for i in 0..n {
+        let j = (n - i) & (n - 1);
+        let a_low = (fa[i] + fa[j].conj()) * half;
+        let a_high = (fa[i] - fa[j].conj()) * half_i;
+        let b_low = (fb[i] + fb[j].conj()) * half;
+        let b_high = (fb[i] - fb[j].conj()) * half_i;
+
+        fx[i] = a_low * b_low + unit_i * (a_high * b_high);
+        fy[i] = a_low * b_high + unit_i * (a_high * b_low);
+    }
+
+    process_fft(&mut fx, true);
+    process_fft(&mut fy, true);
+
+    [
+        fx[..len].iter().map(|c| c.real.round() as i64).collect(),
+        fy[..len]
+            .iter()
+            .map(|c| c.real.round() as i64 + c.imaginary.round() as i64)
+            .collect(),
+        fx[..len]
+            .iter()
+            .map(|c| c.imaginary.round() as i64)
+            .collect(),
+    ]
+}
+
+// Exact convolution of values below 2^30 with f64 FFT, the result is taken modulo 2^64
+// Values are shifted to [-2^29, 2^29) first and the shift is added back with prefix sums
+// Four FFTs replace the nine NTTs of convolution_exact when only the low 64 bits matter
+fn convolution_fft_u64(a: &[u32], b: &[u32]) -> Vec<u64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    const SHIFT: i64 = 1 << 29;
+
+    let a = a.iter().map(|&x| x as i64 - SHIFT).collect::<Vec<_>>();
+    let b = b.iter().map(|&x| x as i64 - SHIFT).collect::<Vec<_>>();
+    let [low, mid, high] = convolution_split(&a, &b);
+    let prefix = |arr: &[i64]| {
+        let mut ret = vec![0; arr.len() + 1];
+
+        for i in 0..arr.len() {
+            ret[i + 1] = ret[i] + arr[i];
+        }
+
+        ret
+    };
+    let prefix_a = prefix(&a);
+    let prefix_b = prefix(&b);
+
+    (0..low.len())
+        .map(|k| {
+            // Pairs (i, k - i) with i in first..=last
+            let first = (k + 1).saturating_sub(b.len());
+            let last = k.min(a.len() - 1);
+            let sum_a = prefix_a[last + 1] - prefix_a[first];
+            let sum_b = prefix_b[k - first + 1] - prefix_b[k - last];
+            let cnt = (last + 1 - first) as i64;
+
+            (low[k] as u64)
+                .wrapping_add((mid[k] as u64).wrapping_shl(15))
+                .wrapping_add((high[k] as u64).wrapping_shl(30))
+                .wrapping_add((SHIFT as u64).wrapping_mul((sum_a + sum_b) as u64))
+                .wrapping_add(((SHIFT * SHIFT) as u64).wrapping_mul(cnt as u64))
+        })
+        .collect()
+}
+
+// Convolution modulo any modular below 2^30 with f64 FFT instead of three NTTs
+// Values are taken in (-modular / 2, modular / 2] to keep the error small
+fn convolution_fft_mod(a: &[u32], b: &[u32], modular: u32) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    let m = modular as i64;
+    let center = |arr: &[u32]| {
+        arr.iter()
+            .map(|&x| {
+                if x as i64 > m / 2 {
+                    x as i64 - m
+                } else {
+                    x as i64
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    let [low, mid, high] = convolution_split(&center(a), &center(b));
+
+    (0..low.len())
+        .map(|i| {
+            let ret = low[i].rem_euclid(m)
+                + (mid[i].rem_euclid(m) << 15) % m
+                + (high[i].rem_euclid(m) << 30) % m;
+            (ret % m) as u32
+        })
+        .collect()
+}
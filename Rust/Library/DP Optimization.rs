@@ -0,0 +1,449 @@
+use std::collections::VecDeque;
+
+const INF: i64 = i64::MAX / 4;
+
+// Sliding window minimum, keys must be pushed in increasing order
+// For a maximum push negated values
+struct MonotoneQueue {
+    deque: VecDeque<(usize, i64)>,
+}
+
+impl MonotoneQueue {
+    fn new() -> Self {
+        Self {
+            deque: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, key: usize, val: i64) {
+        while matches!(self.deque.back(), Some(&(_, v)) if v > val) {
+            self.deque.pop_back();
+        }
+
+        self.deque.push_back((key, val));
+    }
+
+    // Drops every key below `key`
+    fn expire(&mut self, key: usize) {
+        while matches!(self.deque.front(), Some(&(k, _)) if k < key) {
+            self.deque.pop_front();
+        }
+    }
+
+    // (key, value) of the minimum, the earliest key among ties
+    fn min(&self) -> Option<(usize, i64)> {
+        self.deque.front().copied()
+    }
+}
+
+fn process_monotone_minima(
+    f: &impl Fn(usize, usize) -> i64,
+    rows: (usize, usize),
+    cols: (usize, usize),
+    ret: &mut [usize],
+) {
+    let (top, bottom) = rows;
+
+    if top >= bottom {
+        return;
+    }
+
+    let mid = (top + bottom) / 2;
+    let mut best = cols.0;
+
+    for j in cols.0..=cols.1 {
+        if f(mid, j) < f(mid, best) {
+            best = j;
+        }
+    }
+
+    ret[mid] = best;
+    process_monotone_minima(f, (top, mid), (cols.0, best), ret);
+    process_monotone_minima(f, (mid + 1, bottom), (best, cols.1), ret);
+}
+
+// Leftmost column of the minimum of every row in O((n + m) log n)
+// The argmin must be nondecreasing from row to row
+fn monotone_minima(n: usize, m: usize, f: impl Fn(usize, usize) -> i64) -> Vec<usize> {
+    let mut ret = vec![0; n];
+
+    if m > 0 {
+        process_monotone_minima(&f, (0, n), (0, m - 1), &mut ret);
+    }
+
+    ret
+}
+
+fn process_smawk(
+    f: &impl Fn(usize, usize) -> i64,
+    rows: &[usize],
+    cols: &[usize],
+    ret: &mut [usize],
+) {
+    if rows.is_empty() {
+        return;
+    }
+
+    // Reduce: at most one surviving column per row, a column loses once a later one beats it
+    let mut stack: Vec<usize> = Vec::with_capacity(rows.len());
+
+    for &c in cols.iter() {
+        while let Some(&top) = stack.last() {
+            let r = rows[stack.len() - 1];
+
+            if f(r, top) <= f(r, c) {
+                break;
+            }
+
+            stack.pop();
+        }
+
+        if stack.len() < rows.len() {
+            stack.push(c);
+        }
+    }
+
+    let odd = rows.iter().skip(1).step_by(2).copied().collect::<Vec<_>>();
+    process_smawk(f, &odd, &stack, ret);
+
+    // Interpolate: each even row searches between the answers of its odd neighbours
+    let mut k = 0;
+
+    for i in (0..rows.len()).step_by(2) {
+        let r = rows[i];
+        let last = if i + 1 < rows.len() {
+            ret[rows[i + 1]]
+        } else {
+            *stack.last().unwrap()
+        };
+        let mut best = stack[k];
+
+        while stack[k] != last {
+            k += 1;
+
+            if f(r, stack[k]) < f(r, best) {
+                best = stack[k];
+            }
+        }
+
+        ret[r] = best;
+    }
+}
+
+// Reference: https://noshi91.github.io/Library/algorithm/smawk.cpp
+// Column of a minimum of every row of a totally monotone matrix in O(n + m)
+fn smawk(n: usize, m: usize, f: impl Fn(usize, usize) -> i64) -> Vec<usize> {
+    let mut ret = vec![0; n];
+
+    if m > 0 {
+        let rows = (0..n).collect::<Vec<_>>();
+        let cols = (0..m).collect::<Vec<_>>();
+        process_smawk(&f, &rows, &cols, &mut ret);
+    }
+
+    ret
+}
+
+#[allow(clippy::too_many_arguments)]
+fn process_divide_and_conquer_dp(
+    prev: &[i64],
+    cost: &impl Fn(usize, usize) -> i64,
+    start: usize,
+    end: usize,
+    opt_left: usize,
+    opt_right: usize,
+    next: &mut [i64],
+    opt: &mut [usize],
+) {
+    if start > end {
+        return;
+    }
+
+    let mid = (start + end) / 2;
+    let hi = opt_right.min(mid - 1);
+    let mut best = (INF, opt_left);
+
+    for (j, &p) in prev.iter().enumerate().take(hi + 1).skip(opt_left) {
+        if p >= INF {
+            continue;
+        }
+
+        let val = p + cost(j, mid);
+
+        if val < best.0 {
+            best = (val, j);
+        }
+    }
+
+    next[mid] = best.0;
+    opt[mid] = best.1;
+
+    if mid > start {
+        process_divide_and_conquer_dp(prev, cost, start, mid - 1, opt_left, best.1, next, opt);
+    }
+
+    process_divide_and_conquer_dp(prev, cost, mid + 1, end, best.1, opt_right, next, opt);
+}
+
+// One layer of next[i] = min over j < i of prev[j] + cost(j, i) for 1 <= i <= n
+// in O(n log n) calls to cost, the best j must be nondecreasing in i
+// next[0] is INF and opt[i] keeps the chosen j for reconstruction
+fn divide_and_conquer_dp(
+    prev: &[i64],
+    cost: impl Fn(usize, usize) -> i64,
+) -> (Vec<i64>, Vec<usize>) {
+    let n = prev.len() - 1;
+    let mut next = vec![INF; n + 1];
+    let mut opt = vec![0; n + 1];
+
+    if n >= 1 {
+        process_divide_and_conquer_dp(prev, &cost, 1, n, 0, n - 1, &mut next, &mut opt);
+    }
+
+    (next, opt)
+}
+
+// Reference: https://en.wikipedia.org/wiki/Knuth%27s_optimization
+// dp[i][j] = min over i < k < j of dp[i][k] + dp[k][j] + cost(i, j) on points 0..=n
+// with dp[i][i + 1] = 0, in O(n^2) when cost satisfies the quadrangle inequality
+// and is monotone on inclusion, dp[i][j] covers the elements i..j
+// Returns dp and the chosen split of every interval
+fn knuth_interval_dp(
+    n: usize,
+    cost: impl Fn(usize, usize) -> i64,
+) -> (Vec<Vec<i64>>, Vec<Vec<usize>>) {
+    let mut dp = vec![vec![0; n + 1]; n + 1];
+    let mut opt = vec![vec![0; n + 1]; n + 1];
+
+    for i in 0..n {
+        opt[i][i + 1] = i + 1;
+    }
+
+    for len in 2..=n {
+        for i in 0..=n - len {
+            let j = i + len;
+            let lo = opt[i][j - 1].max(i + 1);
+            let hi = opt[i + 1][j].min(j - 1);
+            let mut best = (INF, lo);
+
+            for (k, row) in dp.iter().enumerate().take(hi + 1).skip(lo) {
+                let val = dp[i][k] + row[j];
+
+                if val < best.0 {
+                    best = (val, k);
+                }
+            }
+
+            dp[i][j] = best.0 + cost(i, j);
+            opt[i][j] = best.1;
+        }
+    }
+
+    (dp, opt)
+}
+
+// Reference: https://koosaga.com/243
+// Minimum cost using exactly k pieces when that minimum is convex in k
+// solve(lambda) minimizes cost + lambda * pieces and returns (that minimum, fewest pieces
+// among its optimal solutions), lambda is searched in [lo, hi] where solve(hi) uses
+// at most k pieces and solve(lo) at least k
+fn aliens_trick(
+    k: usize,
+    mut lo: i64,
+    mut hi: i64,
+    mut solve: impl FnMut(i64) -> (i64, usize),
+) -> i64 {
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+
+        if solve(mid).1 <= k {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+
+    solve(lo).0 - lo * k as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self, n: u64) -> u64 {
+            self.0 ^= self.0 << 7;
+            self.0 ^= self.0 >> 9;
+            self.0 % n
+        }
+    }
+
+    fn prefix_sums(rng: &mut Rng, n: usize) -> Vec<i64> {
+        let mut ret = vec![0; n + 1];
+
+        for i in 0..n {
+            ret[i + 1] = ret[i] + rng.next(20) as i64;
+        }
+
+        ret
+    }
+
+    #[test]
+    fn monotone_queue_keeps_earliest_minimum() {
+        let mut rng = Rng(88172645463325252);
+
+        for _ in 0..200 {
+            let n = rng.next(40) as usize + 1;
+            let w = rng.next(8) as usize + 1;
+            let vals = (0..n).map(|_| rng.next(4) as i64).collect::<Vec<_>>();
+            let mut queue = MonotoneQueue::new();
+
+            for i in 0..n {
+                queue.push(i, vals[i]);
+                queue.expire((i + 1).saturating_sub(w));
+
+                let lo = (i + 1).saturating_sub(w);
+                let best = (lo..=i).min_by_key(|&j| (vals[j], j)).unwrap();
+
+                assert_eq!(queue.min(), Some((best, vals[best])));
+            }
+        }
+    }
+
+    #[test]
+    fn minima_of_monge_matrices() {
+        let mut rng = Rng(1234567);
+
+        for _ in 0..200 {
+            let n = rng.next(30) as usize + 1;
+            let m = rng.next(30) as usize + 1;
+            let mut x = (0..n).map(|_| rng.next(50) as i64).collect::<Vec<_>>();
+            let mut y = (0..m).map(|_| rng.next(50) as i64).collect::<Vec<_>>();
+
+            x.sort_unstable();
+            y.sort_unstable();
+
+            let f = |i: usize, j: usize| (x[i] - y[j]) * (x[i] - y[j]);
+            let leftmost = (0..n)
+                .map(|i| (0..m).min_by_key(|&j| (f(i, j), j)).unwrap())
+                .collect::<Vec<_>>();
+
+            assert_eq!(monotone_minima(n, m, f), leftmost);
+
+            for (i, j) in smawk(n, m, f).into_iter().enumerate() {
+                assert_eq!(f(i, j), f(i, leftmost[i]));
+            }
+        }
+    }
+
+    #[test]
+    fn divide_and_conquer_matches_brute_force() {
+        let mut rng = Rng(987654321);
+
+        for _ in 0..200 {
+            let n = rng.next(30) as usize;
+            let sum = prefix_sums(&mut rng, n);
+            let cost = |j: usize, i: usize| (sum[i] - sum[j]) * (sum[i] - sum[j]) + 7;
+            let mut prev = vec![INF; n + 1];
+            prev[0] = 0;
+
+            for _ in 0..3 {
+                let (next, opt) = divide_and_conquer_dp(&prev, cost);
+
+                for i in 1..=n {
+                    let best = (0..i)
+                        .filter(|&j| prev[j] < INF)
+                        .map(|j| prev[j] + cost(j, i))
+                        .min()
+                        .unwrap_or(INF);
+
+                    assert_eq!(next[i], best);
+
+                    if best < INF {
+                        assert_eq!(prev[opt[i]] + cost(opt[i], i), best);
+                    }
+                }
+
+                prev = next;
+            }
+        }
+    }
+
+    #[test]
+    fn knuth_matches_cubic_dp() {
+        let mut rng = Rng(55555);
+
+        for _ in 0..200 {
+            let n = rng.next(25) as usize + 1;
+            let sum = prefix_sums(&mut rng, n);
+            let cost = |i: usize, j: usize| sum[j] - sum[i];
+            let (dp, opt) = knuth_interval_dp(n, cost);
+            let mut brute = vec![vec![0; n + 1]; n + 1];
+
+            for len in 2..=n {
+                for i in 0..=n - len {
+                    let j = i + len;
+                    let best = (i + 1..j).map(|k| brute[i][k] + brute[k][j]).min().unwrap();
+
+                    brute[i][j] = best + cost(i, j);
+                    assert_eq!(dp[i][j], brute[i][j]);
+
+                    let k = opt[i][j];
+                    assert_eq!(dp[i][k] + dp[k][j] + cost(i, j), dp[i][j]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn aliens_trick_matches_k_partition() {
+        let mut rng = Rng(31415926);
+
+        for _ in 0..100 {
+            let n = rng.next(20) as usize + 1;
+            let sum = prefix_sums(&mut rng, n);
+            let cost = |j: usize, i: usize| (sum[i] - sum[j]) * (sum[i] - sum[j]);
+
+            // brute[k][i] splits the first i elements into exactly k pieces
+            let mut brute = vec![vec![INF; n + 1]];
+            brute[0][0] = 0;
+
+            for _ in 1..=n {
+                let prev = brute.last().unwrap();
+                let next = (0..=n)
+                    .map(|i| {
+                        (0..i)
+                            .filter(|&j| prev[j] < INF)
+                            .map(|j| prev[j] + cost(j, i))
+                            .min()
+                            .unwrap_or(INF)
+                    })
+                    .collect();
+
+                brute.push(next);
+            }
+
+            let solve = |lambda: i64| {
+                let mut dp = vec![(INF, 0); n + 1];
+                dp[0] = (0, 0);
+
+                for i in 1..=n {
+                    for j in 0..i {
+                        let cand = (dp[j].0 + cost(j, i) + lambda, dp[j].1 + 1);
+                        dp[i] = dp[i].min(cand);
+                    }
+                }
+
+                dp[n]
+            };
+
+            let bound = sum[n] * sum[n] + 1;
+
+            for (k, row) in brute.iter().enumerate().skip(1) {
+                assert_eq!(aliens_trick(k, -bound, bound, solve), row[n]);
+            }
+        }
+    }
+}
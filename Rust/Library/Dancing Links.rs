@@ -0,0 +1,381 @@
+// Reference: https://arxiv.org/abs/cs/0011047
+// Knuth's Algorithm X on dancing links for exact cover
+// Primary columns must be covered exactly once, secondary columns at most once
+// Every chosen row covers a primary column, rows without one never take part
+// Node 0 is the root, node c + 1 the header of column c and rows follow
+struct DancingLinks {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    col: Vec<usize>,
+    row: Vec<usize>,
+    size: Vec<usize>,
+    num_rows: usize,
+}
+
+impl DancingLinks {
+    fn new(primary: usize, secondary: usize) -> Self {
+        let headers = primary + secondary + 1;
+        let mut ret = Self {
+            left: (0..headers).collect(),
+            right: (0..headers).collect(),
+            up: (0..headers).collect(),
+            down: (0..headers).collect(),
+            col: (0..headers).collect(),
+            row: vec![usize::MAX; headers],
+            size: vec![0; headers],
+            num_rows: 0,
+        };
+
+        // Only primary headers join the ring of the root, so a secondary column is never chosen
+        for i in 0..=primary {
+            let next = (i + 1) % (primary + 1);
+            ret.right[i] = next;
+            ret.left[next] = i;
+        }
+
+        ret
+    }
+
+    // Adds a row covering the given columns and returns its index
+    fn add_row(&mut self, cols: &[usize]) -> usize {
+        let r = self.num_rows;
+        let mut first = None;
+
+        self.num_rows += 1;
+
+        for &c in cols.iter() {
+            let header = c + 1;
+            let x = self.left.len();
+
+            self.col.push(header);
+            self.row.push(r);
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            self.down[self.up[header]] = x;
+            self.up[header] = x;
+            self.size[header] += 1;
+
+            match first {
+                Some(f) => {
+                    self.left.push(self.left[f]);
+                    self.right.push(f);
+                    self.right[self.left[f]] = x;
+                    self.left[f] = x;
+                }
+                None => {
+                    self.left.push(x);
+                    self.right.push(x);
+                    first = Some(x);
+                }
+            }
+        }
+
+        r
+    }
+
+    fn cover(&mut self, c: usize) {
+        let (l, r) = (self.left[c], self.right[c]);
+        self.right[l] = r;
+        self.left[r] = l;
+
+        let mut i = self.down[c];
+
+        while i != c {
+            let mut j = self.right[i];
+
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = d;
+                self.up[d] = u;
+                self.size[self.col[j]] -= 1;
+                j = self.right[j];
+            }
+
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, c: usize) {
+        let mut i = self.up[c];
+
+        while i != c {
+            let mut j = self.left[i];
+
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.size[self.col[j]] += 1;
+                self.down[u] = j;
+                self.up[d] = j;
+                j = self.left[j];
+            }
+
+            i = self.up[i];
+        }
+
+        let (l, r) = (self.left[c], self.right[c]);
+        self.right[l] = c;
+        self.left[r] = c;
+    }
+
+    // Returns true once visit asks to stop
+    fn process_search(
+        &mut self,
+        partial: &mut Vec<usize>,
+        visit: &mut impl FnMut(&[usize]) -> bool,
+    ) -> bool {
+        if self.right[0] == 0 {
+            return visit(partial);
+        }
+
+        // The column with the fewest rows left keeps the branching small
+        let mut c = self.right[0];
+        let mut j = self.right[c];
+
+        while j != 0 {
+            if self.size[j] < self.size[c] {
+                c = j;
+            }
+
+            j = self.right[j];
+        }
+
+        if self.size[c] == 0 {
+            return false;
+        }
+
+        self.cover(c);
+
+        let mut r = self.down[c];
+        let mut stop = false;
+
+        while r != c && !stop {
+            partial.push(self.row[r]);
+
+            let mut j = self.right[r];
+
+            while j != r {
+                self.cover(self.col[j]);
+                j = self.right[j];
+            }
+
+            stop = self.process_search(partial, visit);
+
+            let mut j = self.left[r];
+
+            while j != r {
+                self.uncover(self.col[j]);
+                j = self.left[j];
+            }
+
+            partial.pop();
+            r = self.down[r];
+        }
+
+        self.uncover(c);
+        stop
+    }
+
+    // Calls visit with the rows of every solution until it returns true
+    fn solve_with(&mut self, mut visit: impl FnMut(&[usize]) -> bool) {
+        self.process_search(&mut Vec::new(), &mut visit);
+    }
+
+    // Rows of a solution in increasing order
+    fn solve_first(&mut self) -> Option<Vec<usize>> {
+        let mut ret = None;
+
+        self.solve_with(|rows| {
+            let mut rows = rows.to_vec();
+            rows.sort_unstable();
+            ret = Some(rows);
+
+            true
+        });
+
+        ret
+    }
+
+    fn solve_all(&mut self) -> Vec<Vec<usize>> {
+        let mut ret = Vec::new();
+
+        self.solve_with(|rows| {
+            let mut rows = rows.to_vec();
+            rows.sort_unstable();
+            ret.push(rows);
+
+            false
+        });
+
+        ret
+    }
+
+    fn count(&mut self) -> u64 {
+        let mut ret = 0;
+
+        self.solve_with(|_| {
+            ret += 1;
+            false
+        });
+
+        ret
+    }
+}
+
+// Sudoku of side n * n with n x n boxes, grid[r][c] is 0 when empty or a value in 1..=n * n
+// Row i of the cover places the value v in the cell (r, c) given by the i-th entry
+fn sudoku_cover(n: usize, grid: &[Vec<usize>]) -> (DancingLinks, Vec<(usize, usize, usize)>) {
+    let side = n * n;
+    let cells = side * side;
+    let mut dlx = DancingLinks::new(4 * cells, 0);
+    let mut placements = Vec::new();
+
+    for (r, row) in grid.iter().enumerate().take(side) {
+        for (c, &clue) in row.iter().enumerate().take(side) {
+            let values = if clue == 0 {
+                1..side + 1
+            } else {
+                clue..clue + 1
+            };
+
+            for v in values {
+                let b = r / n * n + c / n;
+
+                dlx.add_row(&[
+                    r * side + c,
+                    cells + r * side + v - 1,
+                    2 * cells + c * side + v - 1,
+                    3 * cells + b * side + v - 1,
+                ]);
+                placements.push((r, c, v));
+            }
+        }
+    }
+
+    (dlx, placements)
+}
+
+// The filled grid, None when the clues admit no solution
+fn solve_sudoku(n: usize, grid: &[Vec<usize>]) -> Option<Vec<Vec<usize>>> {
+    let (mut dlx, placements) = sudoku_cover(n, grid);
+    let rows = dlx.solve_first()?;
+    let mut ret = grid.to_vec();
+
+    for idx in rows {
+        let (r, c, v) = placements[idx];
+        ret[r][c] = v;
+    }
+
+    Some(ret)
+}
+
+// Every row and column holds a queen, diagonals are secondary columns
+// Row i of the cover puts a queen on the square given by the i-th entry
+fn n_queens_cover(n: usize) -> (DancingLinks, Vec<(usize, usize)>) {
+    let diagonals = 2 * n.max(1) - 1;
+    let mut dlx = DancingLinks::new(2 * n, 2 * diagonals);
+    let mut squares = Vec::with_capacity(n * n);
+
+    for r in 0..n {
+        for c in 0..n {
+            dlx.add_row(&[r, n + c, 2 * n + r + c, 2 * n + diagonals + r + n - 1 - c]);
+            squares.push((r, c));
+        }
+    }
+
+    (dlx, squares)
+}
+
+// Distinct orientations of a piece, each shifted so that its smallest coordinates are 0
+fn polyomino_orientations(piece: &[(usize, usize)], reflect: bool) -> Vec<Vec<(usize, usize)>> {
+    let mut ret = Vec::new();
+    let mut cells = piece
+        .iter()
+        .map(|&(r, c)| (r as i64, c as i64))
+        .collect::<Vec<_>>();
+
+    for flip in 0..if reflect { 2 } else { 1 } {
+        if flip == 1 {
+            cells = cells.iter().map(|&(r, c)| (r, -c)).collect();
+        }
+
+        for _ in 0..4 {
+            cells = cells.iter().map(|&(r, c)| (c, -r)).collect();
+
+            let min_r = cells.iter().map(|&(r, _)| r).min().unwrap_or(0);
+            let min_c = cells.iter().map(|&(_, c)| c).min().unwrap_or(0);
+            let mut shape = cells
+                .iter()
+                .map(|&(r, c)| ((r - min_r) as usize, (c - min_c) as usize))
+                .collect::<Vec<_>>();
+
+            shape.sort_unstable();
+            ret.push(shape);
+        }
+    }
+
+    ret.sort_unstable();
+    ret.dedup();
+    ret
+}
+
+// Piece index and the board cells it covers
+type Placement = (usize, Vec<(usize, usize)>);
+
+// Tiles the open cells of board with the pieces, rotated and optionally reflected
+// With each_once every piece is used exactly once, otherwise any number of times
+// Row i of the cover places the piece on the cells given by the i-th entry
+fn polyomino_cover(
+    board: &[Vec<bool>],
+    pieces: &[Vec<(usize, usize)>],
+    reflect: bool,
+    each_once: bool,
+) -> (DancingLinks, Vec<Placement>) {
+    let mut cell_id = board
+        .iter()
+        .map(|row| vec![usize::MAX; row.len()])
+        .collect::<Vec<_>>();
+    let mut num_cells = 0;
+
+    for (r, row) in board.iter().enumerate() {
+        for (c, &open) in row.iter().enumerate() {
+            if open {
+                cell_id[r][c] = num_cells;
+                num_cells += 1;
+            }
+        }
+    }
+
+    let offset = if each_once { pieces.len() } else { 0 };
+    let mut dlx = DancingLinks::new(offset + num_cells, 0);
+    let mut placements = Vec::new();
+
+    for (idx, piece) in pieces.iter().enumerate() {
+        for shape in polyomino_orientations(piece, reflect) {
+            for r in 0..board.len() {
+                for c in 0..board[r].len() {
+                    let cells = shape
+                        .iter()
+                        .map(|&(dr, dc)| (r + dr, c + dc))
+                        .collect::<Vec<_>>();
+                    let fits = cells
+                        .iter()
+                        .all(|&(r, c)| r < board.len() && c < board[r].len() && board[r][c]);
+
+                    if !fits {
+                        continue;
+                    }
+
+                    let mut cols = if each_once { vec![idx] } else { Vec::new() };
+                    cols.extend(cells.iter().map(|&(r, c)| offset + cell_id[r][c]));
+
+                    dlx.add_row(&cols);
+                    placements.push((idx, cells));
+                }
+            }
+        }
+    }
+
+    (dlx, placements)
+}
@@ -0,0 +1,361 @@
+use std::collections::HashMap;
+
+// Union by size with path compression, near constant time per operation
+struct DisjointSets {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    num_sets: usize,
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            num_sets: n,
+        }
+    }
+
+    fn find(&mut self, u: usize) -> usize {
+        let mut root = u;
+
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        let mut u = u;
+
+        while self.parent[u] != root {
+            let next = self.parent[u];
+            self.parent[u] = root;
+            u = next;
+        }
+
+        root
+    }
+
+    // Returns false when u and v were already together
+    fn merge(&mut self, u: usize, v: usize) -> bool {
+        let (mut pu, mut pv) = (self.find(u), self.find(v));
+
+        if pu == pv {
+            return false;
+        }
+
+        if self.size[pu] < self.size[pv] {
+            std::mem::swap(&mut pu, &mut pv);
+        }
+
+        self.parent[pv] = pu;
+        self.size[pu] += self.size[pv];
+        self.num_sets -= 1;
+
+        true
+    }
+
+    fn same(&mut self, u: usize, v: usize) -> bool {
+        self.find(u) == self.find(v)
+    }
+
+    fn size(&mut self, u: usize) -> usize {
+        let root = self.find(u);
+        self.size[root]
+    }
+}
+
+// Union by size without path compression, so every merge can be undone in O(1)
+// find is O(log n) and needs no mutable access
+struct RollbackDisjointSets {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    num_sets: usize,
+    // Roots (attached, kept) of every successful merge
+    history: Vec<(usize, usize)>,
+}
+
+impl RollbackDisjointSets {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            num_sets: n,
+            history: Vec::new(),
+        }
+    }
+
+    fn find(&self, mut u: usize) -> usize {
+        while self.parent[u] != u {
+            u = self.parent[u];
+        }
+
+        u
+    }
+
+    fn merge(&mut self, u: usize, v: usize) -> bool {
+        let (mut pu, mut pv) = (self.find(u), self.find(v));
+
+        if pu == pv {
+            return false;
+        }
+
+        if self.size[pu] < self.size[pv] {
+            std::mem::swap(&mut pu, &mut pv);
+        }
+
+        self.parent[pv] = pu;
+        self.size[pu] += self.size[pv];
+        self.num_sets -= 1;
+        self.history.push((pv, pu));
+
+        true
+    }
+
+    fn same(&self, u: usize, v: usize) -> bool {
+        self.find(u) == self.find(v)
+    }
+
+    fn size(&self, u: usize) -> usize {
+        self.size[self.find(u)]
+    }
+
+    // Time to roll back to, counting successful merges
+    fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    // Undoes the last successful merge, returns false when there is none
+    fn undo(&mut self) -> bool {
+        let Some((pv, pu)) = self.history.pop() else {
+            return false;
+        };
+
+        self.parent[pv] = pv;
+        self.size[pu] -= self.size[pv];
+        self.num_sets += 1;
+
+        true
+    }
+
+    fn rollback(&mut self, time: usize) {
+        while self.history.len() > time {
+            self.undo();
+        }
+    }
+}
+
+// Potentials over a group, diff[v] is value[v] - value[parent[v]] and becomes
+// value[v] - value[root] once find has compressed the path of v
+struct PotentialDisjointSets<T> {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    diff: Vec<T>,
+}
+
+impl<T> PotentialDisjointSets<T>
+where
+    T: Copy
+        + Default
+        + PartialEq
+        + std::ops::Add<Output = T>
+        + std::ops::Sub<Output = T>
+        + std::ops::Neg<Output = T>,
+{
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            diff: vec![T::default(); n],
+        }
+    }
+
+    fn find(&mut self, u: usize) -> usize {
+        let mut path = Vec::new();
+        let mut root = u;
+
+        while self.parent[root] != root {
+            path.push(root);
+            root = self.parent[root];
+        }
+
+        // From the node below the root downwards, each parent already points at the root
+        for &v in path.iter().rev() {
+            let p = self.parent[v];
+
+            if p != root {
+                self.diff[v] = self.diff[v] + self.diff[p];
+                self.parent[v] = root;
+            }
+        }
+
+        root
+    }
+
+    // value[u] - value[root of u]
+    fn potential(&mut self, u: usize) -> T {
+        self.find(u);
+        self.diff[u]
+    }
+
+    // Records value[v] - value[u] = w, returns false if it contradicts what is known
+    fn merge(&mut self, u: usize, v: usize, w: T) -> bool {
+        let (pu, pv) = (self.find(u), self.find(v));
+
+        // value[pv] - value[pu] follows from w and both potentials
+        let mut w = w + self.diff[u] - self.diff[v];
+
+        if pu == pv {
+            return w == T::default();
+        }
+
+        let (mut pu, mut pv) = (pu, pv);
+
+        if self.size[pu] < self.size[pv] {
+            std::mem::swap(&mut pu, &mut pv);
+            w = -w;
+        }
+
+        self.parent[pv] = pu;
+        self.size[pu] += self.size[pv];
+        self.diff[pv] = w;
+
+        true
+    }
+
+    // value[v] - value[u] when u and v are in the same set
+    fn diff(&mut self, u: usize, v: usize) -> Option<T> {
+        if self.find(u) != self.find(v) {
+            return None;
+        }
+
+        Some(self.diff[v] - self.diff[u])
+    }
+
+    fn same(&mut self, u: usize, v: usize) -> bool {
+        self.find(u) == self.find(v)
+    }
+
+    fn size(&mut self, u: usize) -> usize {
+        let root = self.find(u);
+        self.size[root]
+    }
+}
+
+fn process_time_segment(
+    tree: &[Vec<(usize, usize)>],
+    node: usize,
+    lo: usize,
+    hi: usize,
+    dsu: &mut RollbackDisjointSets,
+    visit: &mut impl FnMut(usize, &RollbackDisjointSets),
+) {
+    let time = dsu.snapshot();
+
+    for &(u, v) in tree[node].iter() {
+        dsu.merge(u, v);
+    }
+
+    if hi - lo == 1 {
+        visit(lo, dsu);
+    } else {
+        let mid = (lo + hi) / 2;
+        process_time_segment(tree, 2 * node, lo, mid, dsu, visit);
+        process_time_segment(tree, 2 * node + 1, mid, hi, dsu, visit);
+    }
+
+    dsu.rollback(time);
+}
+
+fn insert_time_segment(
+    tree: &mut [Vec<(usize, usize)>],
+    node: usize,
+    lo: usize,
+    hi: usize,
+    interval: (usize, usize),
+    edge: (usize, usize),
+) {
+    let (l, r) = interval;
+
+    if r <= lo || hi <= l {
+        return;
+    }
+
+    if l <= lo && hi <= r {
+        tree[node].push(edge);
+        return;
+    }
+
+    let mid = (lo + hi) / 2;
+    insert_time_segment(tree, 2 * node, lo, mid, interval, edge);
+    insert_time_segment(tree, 2 * node + 1, mid, hi, interval, edge);
+}
+
+// Segment tree over times 0..times, each edge (l, r, u, v) lives during l..r
+// visit(t, dsu) sees exactly the edges alive at t merged, in O((m log T + T) log n)
+fn offline_connectivity(
+    n: usize,
+    times: usize,
+    edges: &[(usize, usize, usize, usize)],
+    mut visit: impl FnMut(usize, &RollbackDisjointSets),
+) {
+    if times == 0 {
+        return;
+    }
+
+    let mut tree = vec![Vec::new(); 4 * times];
+
+    for &(l, r, u, v) in edges.iter() {
+        insert_time_segment(&mut tree, 1, 0, times, (l, r.min(times)), (u, v));
+    }
+
+    let mut dsu = RollbackDisjointSets::new(n);
+    process_time_segment(&tree, 1, 0, times, &mut dsu, &mut visit);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ConnectivityOp {
+    Link(usize, usize),
+    Cut(usize, usize),
+    Connected(usize, usize),
+    NumComponents,
+}
+
+// Answers every query in order, Connected as 1 or 0 and NumComponents as the count
+// Parallel edges are counted, a Cut removes one copy of an existing edge
+fn dynamic_connectivity(n: usize, ops: &[ConnectivityOp]) -> Vec<usize> {
+    let mut open: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    let mut edges = Vec::new();
+
+    for (t, op) in ops.iter().enumerate() {
+        match *op {
+            ConnectivityOp::Link(u, v) => {
+                open.entry((u.min(v), u.max(v))).or_default().push(t);
+            }
+            ConnectivityOp::Cut(u, v) => {
+                let key = (u.min(v), u.max(v));
+                let start = open.get_mut(&key).and_then(|starts| starts.pop());
+                let start = start.expect("Cut of an edge that is not there");
+
+                edges.push((start, t, key.0, key.1));
+            }
+            _ => {}
+        }
+    }
+
+    for ((u, v), starts) in open {
+        for start in starts {
+            edges.push((start, ops.len(), u, v));
+        }
+    }
+
+    let mut answers = vec![None; ops.len()];
+
+    offline_connectivity(n, ops.len(), &edges, |t, dsu| {
+        answers[t] = match ops[t] {
+            ConnectivityOp::Connected(u, v) => Some(dsu.same(u, v) as usize),
+            ConnectivityOp::NumComponents => Some(dsu.num_sets),
+            _ => None,
+        };
+    });
+
+    answers.into_iter().flatten().collect()
+}
@@ -0,0 +1,442 @@
+// Requires Convolution.rs for MOD, pow_mod and convolution
+
+fn mod_add(a: u32, b: u32) -> u32 {
+    let ret = a + b;
+
+    if ret >= MOD {
+        ret - MOD
+    } else {
+        ret
+    }
+}
+
+fn mod_sub(a: u32, b: u32) -> u32 {
+    if a >= b {
+        a - b
+    } else {
+        a + MOD - b
+    }
+}
+
+fn mod_mul(a: u32, b: u32) -> u32 {
+    (a as u64 * b as u64 % MOD as u64) as u32
+}
+
+fn mod_inv(a: u32) -> u32 {
+    pow_mod(a as u64, MOD as u64 - 2, MOD as u64) as u32
+}
+
+// Coefficients modulo 998244353, coef[i] is the coefficient of x^i
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct FormalPowerSeries(Vec<u32>);
+
+impl FormalPowerSeries {
+    fn new(coef: Vec<u32>) -> Self {
+        Self(coef)
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn get(&self, i: usize) -> u32 {
+        self.0.get(i).copied().unwrap_or(0)
+    }
+
+    // First n coefficients, padded with zeros
+    fn truncated(&self, n: usize) -> Self {
+        Self((0..n).map(|i| self.get(i)).collect())
+    }
+
+    fn trim(&mut self) {
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+    }
+
+    fn scale(&self, c: u32) -> Self {
+        Self(self.0.iter().map(|&x| mod_mul(x, c)).collect())
+    }
+
+    fn derivative(&self) -> Self {
+        Self(
+            (1..self.len())
+                .map(|i| mod_mul(self.0[i], i as u32))
+                .collect(),
+        )
+    }
+
+    fn integral(&self) -> Self {
+        let n = self.len();
+        let mut inv = vec![1; n + 1];
+
+        for i in 2..=n {
+            inv[i] = mod_mul(MOD - MOD / i as u32, inv[MOD as usize % i]);
+        }
+
+        let mut ret = vec![0; n + 1];
+
+        for i in 0..n {
+            ret[i + 1] = mod_mul(self.0[i], inv[i + 1]);
+        }
+
+        Self(ret)
+    }
+
+    fn eval(&self, x: u32) -> u32 {
+        self.0
+            .iter()
+            .rev()
+            .fold(0, |acc, &c| mod_add(mod_mul(acc, x), c))
+    }
+
+    // 1 / f mod x^n by Newton iteration, f[0] must be non-zero
+    fn inv(&self, n: usize) -> Self {
+        assert!(self.get(0) != 0);
+
+        let mut g = Self(vec![mod_inv(self.get(0))]);
+        let mut len = 1;
+
+        while len < n {
+            len *= 2;
+
+            let mut h = (&self.truncated(len) * &g).truncated(len);
+
+            for val in h.0.iter_mut() {
+                *val = mod_sub(0, *val);
+            }
+
+            h.0[0] = mod_add(h.0[0], 2);
+            g = (&g * &h).truncated(len);
+        }
+
+        g.truncated(n)
+    }
+
+    // log f mod x^n, f[0] must be 1
+    fn log(&self, n: usize) -> Self {
+        assert!(self.get(0) == 1);
+
+        if n == 0 {
+            return Self(Vec::new());
+        }
+
+        (&self.truncated(n).derivative() * &self.inv(n))
+            .truncated(n - 1)
+            .integral()
+    }
+
+    // exp f mod x^n, f[0] must be 0
+    fn exp(&self, n: usize) -> Self {
+        assert!(self.get(0) == 0);
+
+        let mut g = Self(vec![1]);
+        let mut len = 1;
+
+        while len < n {
+            len *= 2;
+
+            let log_g = g.log(len);
+            let mut h = Self((0..len).map(|i| mod_sub(self.get(i), log_g.0[i])).collect());
+
+            h.0[0] = mod_add(h.0[0], 1);
+            g = (&g * &h).truncated(len);
+        }
+
+        g.truncated(n)
+    }
+
+    // Tonelli-Shanks for the constant term
+    fn sqrt_of_constant(a: u32) -> Option<u32> {
+        let p = MOD as u64;
+        let a = a as u64;
+
+        if a == 0 {
+            return Some(0);
+        }
+
+        if pow_mod(a, (p - 1) / 2, p) != 1 {
+            return None;
+        }
+
+        let mut q = p - 1;
+        let mut s = 0;
+
+        while q.is_multiple_of(2) {
+            q /= 2;
+            s += 1;
+        }
+
+        let z = (2..p)
+            .find(|&z| pow_mod(z, (p - 1) / 2, p) == p - 1)
+            .unwrap();
+        let mut c = pow_mod(z, q, p);
+        let mut r = pow_mod(a, q / 2 + 1, p);
+        let mut t = pow_mod(a, q, p);
+        let mut m = s;
+
+        while t != 1 {
+            let mut i = 0;
+            let mut tt = t;
+
+            while tt != 1 {
+                tt = tt * tt % p;
+                i += 1;
+            }
+
+            let b = pow_mod(c, 1 << (m - i - 1), p);
+            r = r * b % p;
+            c = b * b % p;
+            t = t * c % p;
+            m = i;
+        }
+
+        Some(r.min(p - r) as u32)
+    }
+
+    // A square root of f mod x^n if one exists
+    fn sqrt(&self, n: usize) -> Option<Self> {
+        let low = match self.0.iter().position(|&x| x != 0) {
+            Some(low) => low,
+            None => return Some(Self(vec![0; n])),
+        };
+
+        // f is 0 mod x^n, so 0 is a root whatever the valuation
+        if low >= n {
+            return Some(Self(vec![0; n]));
+        }
+
+        if !low.is_multiple_of(2) {
+            return None;
+        }
+
+        let h = Self(self.0[low..].to_vec());
+        let inv2 = mod_inv(2);
+        let mut g = Self(vec![Self::sqrt_of_constant(h.0[0])?]);
+        let mut len = 1;
+
+        while len < n - low / 2 {
+            len *= 2;
+
+            let t = (&h.truncated(len) * &g.inv(len)).truncated(len);
+            g = (&g.truncated(len) + &t).scale(inv2);
+        }
+
+        let mut ret = vec![0; low / 2];
+        ret.extend(g.0);
+
+        Some(Self(ret).truncated(n))
+    }
+
+    // f^k mod x^n
+    fn pow(&self, k: u64, n: usize) -> Self {
+        if k == 0 {
+            return Self((0..n).map(|i| if i == 0 { 1 } else { 0 }).collect());
+        }
+
+        let low = match self.0.iter().position(|&x| x != 0) {
+            Some(low) => low,
+            None => return Self(vec![0; n]),
+        };
+
+        if low as u128 * k as u128 >= n as u128 {
+            return Self(vec![0; n]);
+        }
+
+        let shift = low * k as usize;
+        let c = self.0[low];
+        let h = Self(self.0[low..].to_vec()).scale(mod_inv(c));
+        let g = h
+            .log(n - shift)
+            .scale((k % MOD as u64) as u32)
+            .exp(n - shift)
+            .scale(pow_mod(c as u64, k, MOD as u64) as u32);
+
+        let mut ret = vec![0; shift];
+        ret.extend(g.0);
+
+        Self(ret)
+    }
+
+    // (quotient, remainder) of polynomial division, both trimmed
+    fn div_rem(&self, other: &Self) -> (Self, Self) {
+        let mut f = self.clone();
+        let mut g = other.clone();
+        f.trim();
+        g.trim();
+
+        assert!(!g.0.is_empty());
+
+        if f.len() < g.len() {
+            return (Self(Vec::new()), f);
+        }
+
+        let len = f.len() - g.len() + 1;
+        let rev_f = Self(f.0.iter().rev().copied().collect());
+        let rev_g = Self(g.0.iter().rev().copied().collect());
+        let mut q = (&rev_f.truncated(len) * &rev_g.inv(len)).truncated(len);
+
+        q.0.reverse();
+
+        let mut r = (&f - &(&g * &q)).truncated(g.len() - 1);
+
+        q.trim();
+        r.trim();
+
+        (q, r)
+    }
+
+    // f(x + c)
+    fn taylor_shift(&self, c: u32) -> Self {
+        let n = self.len();
+        let mut fact = vec![1; n + 1];
+
+        for i in 1..=n {
+            fact[i] = mod_mul(fact[i - 1], i as u32);
+        }
+
+        let mut inv_fact = vec![1; n + 1];
+        inv_fact[n] = mod_inv(fact[n]);
+
+        for i in (0..n).rev() {
+            inv_fact[i] = mod_mul(inv_fact[i + 1], i as u32 + 1);
+        }
+
+        let a = (0..n)
+            .map(|i| mod_mul(self.0[n - 1 - i], fact[n - 1 - i]))
+            .collect::<Vec<_>>();
+        let mut power = 1;
+        let b = (0..n)
+            .map(|j| {
+                let ret = mod_mul(power, inv_fact[j]);
+                power = mod_mul(power, c);
+                ret
+            })
+            .collect::<Vec<_>>();
+        let conv = convolution(&a, &b);
+
+        Self(
+            (0..n)
+                .map(|k| mod_mul(conv[n - 1 - k], inv_fact[k]))
+                .collect(),
+        )
+    }
+
+    fn build_subproduct_tree(tree: &mut Vec<Self>, node: usize, xs: &[u32]) {
+        if tree.len() <= node {
+            tree.resize(node + 1, Self::default());
+        }
+
+        if xs.len() == 1 {
+            tree[node] = Self(vec![mod_sub(0, xs[0]), 1]);
+            return;
+        }
+
+        let mid = xs.len() / 2;
+
+        Self::build_subproduct_tree(tree, node * 2, &xs[..mid]);
+        Self::build_subproduct_tree(tree, node * 2 + 1, &xs[mid..]);
+
+        tree[node] = &tree[node * 2] * &tree[node * 2 + 1];
+    }
+
+    fn eval_subproduct_tree(&self, tree: &[Self], node: usize, xs: &[u32], ret: &mut Vec<u32>) {
+        if xs.len() <= 64 {
+            ret.extend(xs.iter().map(|&x| self.eval(x)));
+            return;
+        }
+
+        let mid = xs.len() / 2;
+        let (_, left) = self.div_rem(&tree[node * 2]);
+        let (_, right) = self.div_rem(&tree[node * 2 + 1]);
+
+        left.eval_subproduct_tree(tree, node * 2, &xs[..mid], ret);
+        right.eval_subproduct_tree(tree, node * 2 + 1, &xs[mid..], ret);
+    }
+
+    // f(xs[i]) for every i in O(n log^2 n)
+    fn multipoint_eval(&self, xs: &[u32]) -> Vec<u32> {
+        if xs.is_empty() {
+            return Vec::new();
+        }
+
+        let mut tree = Vec::new();
+        Self::build_subproduct_tree(&mut tree, 1, xs);
+
+        let mut ret = Vec::with_capacity(xs.len());
+        self.div_rem(&tree[1])
+            .1
+            .eval_subproduct_tree(&tree, 1, xs, &mut ret);
+
+        ret
+    }
+
+    fn combine_subproduct_tree(tree: &[Self], node: usize, weights: &[u32]) -> Self {
+        if weights.len() == 1 {
+            return Self(vec![weights[0]]);
+        }
+
+        let mid = weights.len() / 2;
+        let left = Self::combine_subproduct_tree(tree, node * 2, &weights[..mid]);
+        let right = Self::combine_subproduct_tree(tree, node * 2 + 1, &weights[mid..]);
+
+        &(&left * &tree[node * 2 + 1]) + &(&right * &tree[node * 2])
+    }
+
+    // Polynomial of degree below n through (xs[i], ys[i]), xs must be distinct
+    fn interpolate(xs: &[u32], ys: &[u32]) -> Self {
+        if xs.is_empty() {
+            return Self(Vec::new());
+        }
+
+        let mut tree = Vec::new();
+        Self::build_subproduct_tree(&mut tree, 1, xs);
+
+        let mut derivative_values = Vec::with_capacity(xs.len());
+        tree[1]
+            .derivative()
+            .eval_subproduct_tree(&tree, 1, xs, &mut derivative_values);
+
+        let weights = (0..xs.len())
+            .map(|i| mod_mul(ys[i], mod_inv(derivative_values[i])))
+            .collect::<Vec<_>>();
+        let mut ret = Self::combine_subproduct_tree(&tree, 1, &weights);
+
+        ret.trim();
+        ret
+    }
+}
+
+impl std::ops::Add for &FormalPowerSeries {
+    type Output = FormalPowerSeries;
+
+    fn add(self, other: Self) -> FormalPowerSeries {
+        let n = self.len().max(other.len());
+        FormalPowerSeries((0..n).map(|i| mod_add(self.get(i), other.get(i))).collect())
+    }
+}
+
+impl std::ops::Sub for &FormalPowerSeries {
+    type Output = FormalPowerSeries;
+
+    fn sub(self, other: Self) -> FormalPowerSeries {
+        let n = self.len().max(other.len());
+        FormalPowerSeries((0..n).map(|i| mod_sub(self.get(i), other.get(i))).collect())
+    }
+}
+
+impl std::ops::Mul for &FormalPowerSeries {
+    type Output = FormalPowerSeries;
+
+    fn mul(self, other: Self) -> FormalPowerSeries {
+        FormalPowerSeries(convolution(&self.0, &other.0))
+    }
+}
+
+impl std::ops::Neg for &FormalPowerSeries {
+    type Output = FormalPowerSeries;
+
+    fn neg(self) -> FormalPowerSeries {
+        FormalPowerSeries(self.0.iter().map(|&x| mod_sub(0, x)).collect())
+    }
+}
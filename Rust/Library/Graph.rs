@@ -0,0 +1,359 @@
+const UNVISITED: usize = usize::MAX;
+
+// Reference: https://en.wikipedia.org/wiki/Tarjan%27s_strongly_connected_components_algorithm
+// Component of every vertex, numbered in topological order so that every edge u -> v
+// has comp[u] <= comp[v], iterative so that long paths don't overflow the stack
+fn scc(adj: &[Vec<usize>]) -> Vec<usize> {
+    let n = adj.len();
+    let mut ord = vec![UNVISITED; n];
+    let mut low = vec![0; n];
+    let mut comp = vec![UNVISITED; n];
+    let mut visited = Vec::with_capacity(n);
+    let mut stack = Vec::new();
+    let mut cnt = 0;
+    let mut num_comps = 0;
+
+    for start in 0..n {
+        if ord[start] != UNVISITED {
+            continue;
+        }
+
+        ord[start] = cnt;
+        low[start] = cnt;
+        cnt += 1;
+        visited.push(start);
+        stack.push((start, 0));
+
+        while let Some(&mut (vertex, ref mut idx)) = stack.last_mut() {
+            if let Some(&next) = adj[vertex].get(*idx) {
+                *idx += 1;
+
+                if ord[next] == UNVISITED {
+                    ord[next] = cnt;
+                    low[next] = cnt;
+                    cnt += 1;
+                    visited.push(next);
+                    stack.push((next, 0));
+                } else if comp[next] == UNVISITED {
+                    low[vertex] = low[vertex].min(ord[next]);
+                }
+
+                continue;
+            }
+
+            stack.pop();
+
+            if let Some(&(parent, _)) = stack.last() {
+                low[parent] = low[parent].min(low[vertex]);
+            }
+
+            if low[vertex] == ord[vertex] {
+                loop {
+                    let top = visited.pop().unwrap();
+                    comp[top] = num_comps;
+
+                    if top == vertex {
+                        break;
+                    }
+                }
+
+                num_comps += 1;
+            }
+        }
+    }
+
+    // Tarjan finishes the components in reverse topological order
+    for c in comp.iter_mut() {
+        *c = num_comps - 1 - *c;
+    }
+
+    comp
+}
+
+// DAG of the strongly connected components, groups and dag are in topological order
+struct Condensation {
+    comp: Vec<usize>,
+    groups: Vec<Vec<usize>>,
+    dag: Vec<Vec<usize>>,
+}
+
+impl Condensation {
+    fn new(adj: &[Vec<usize>]) -> Self {
+        let comp = scc(adj);
+        let num_comps = comp.iter().map(|&c| c + 1).max().unwrap_or(0);
+        let mut groups = vec![Vec::new(); num_comps];
+        let mut dag = vec![Vec::new(); num_comps];
+
+        for (vertex, edges) in adj.iter().enumerate() {
+            groups[comp[vertex]].push(vertex);
+
+            for &next in edges.iter() {
+                if comp[vertex] != comp[next] {
+                    dag[comp[vertex]].push(comp[next]);
+                }
+            }
+        }
+
+        for edges in dag.iter_mut() {
+            edges.sort_unstable();
+            edges.dedup();
+        }
+
+        Self { comp, groups, dag }
+    }
+}
+
+// Literal (x, true) is the vertex 2x and (x, false) is 2x + 1
+struct TwoSat {
+    n: usize,
+    adj: Vec<Vec<usize>>,
+}
+
+impl TwoSat {
+    fn new(n: usize) -> Self {
+        Self {
+            n,
+            adj: vec![Vec::new(); 2 * n],
+        }
+    }
+
+    fn literal(x: usize, val: bool) -> usize {
+        2 * x + !val as usize
+    }
+
+    // Fresh variable, used by at_most_one
+    fn add_var(&mut self) -> usize {
+        self.adj.push(Vec::new());
+        self.adj.push(Vec::new());
+        self.n += 1;
+
+        self.n - 1
+    }
+
+    // (x = a) implies (y = b), together with its contrapositive
+    fn implies(&mut self, x: usize, a: bool, y: usize, b: bool) {
+        self.adj[Self::literal(x, a)].push(Self::literal(y, b));
+        self.adj[Self::literal(y, !b)].push(Self::literal(x, !a));
+    }
+
+    // (x = a) or (y = b)
+    fn add_clause(&mut self, x: usize, a: bool, y: usize, b: bool) {
+        self.implies(x, !a, y, b);
+    }
+
+    fn set(&mut self, x: usize, a: bool) {
+        self.add_clause(x, a, x, a);
+    }
+
+    // At most one of the literals holds, with O(k) clauses over prefix variables
+    fn at_most_one(&mut self, literals: &[(usize, bool)]) {
+        if literals.len() <= 1 {
+            return;
+        }
+
+        // prefix is true once some literal up to the current one holds
+        let mut prefix = self.add_var();
+        self.implies(literals[0].0, literals[0].1, prefix, true);
+
+        for &(x, a) in literals.iter().skip(1) {
+            let next = self.add_var();
+
+            self.implies(x, a, next, true);
+            self.implies(prefix, true, next, true);
+            self.implies(prefix, true, x, !a);
+            prefix = next;
+        }
+    }
+
+    // Exactly one of (x = a) and (y = b), longer lists would need clauses 2-SAT cannot express
+    fn exactly_one(&mut self, x: usize, a: bool, y: usize, b: bool) {
+        self.add_clause(x, a, y, b);
+        self.add_clause(x, !a, y, !b);
+    }
+
+    // Values of every variable, None when the formula is unsatisfiable
+    // Variables added by at_most_one come after the ones given to new
+    fn solve(&self) -> Option<Vec<bool>> {
+        let comp = scc(&self.adj);
+        let mut ret = Vec::with_capacity(self.n);
+
+        for x in 0..self.n {
+            let (t, f) = (comp[2 * x], comp[2 * x + 1]);
+
+            if t == f {
+                return None;
+            }
+
+            // The literal later in topological order cannot reach its negation
+            ret.push(t > f);
+        }
+
+        Some(ret)
+    }
+}
+
+// Reference: https://cp-algorithms.com/graph/bridge-searching.html
+// Lowlink of an undirected multigraph, edges are kept by index so that parallel edges work
+// blocks holds the vertices of every biconnected component, isolated vertices included
+struct LowLink {
+    adj: Vec<Vec<(usize, usize)>>,
+    ord: Vec<usize>,
+    low: Vec<usize>,
+    bridges: Vec<usize>,
+    is_cut: Vec<bool>,
+    blocks: Vec<Vec<usize>>,
+}
+
+impl LowLink {
+    fn new(n: usize, edges: &[(usize, usize)]) -> Self {
+        let mut adj = vec![Vec::new(); n];
+
+        for (idx, &(u, v)) in edges.iter().enumerate() {
+            adj[u].push((v, idx));
+            adj[v].push((u, idx));
+        }
+
+        let mut ret = Self {
+            adj,
+            ord: vec![UNVISITED; n],
+            low: vec![0; n],
+            bridges: Vec::new(),
+            is_cut: vec![false; n],
+            blocks: Vec::new(),
+        };
+
+        ret.construct();
+        ret
+    }
+
+    fn construct(&mut self) {
+        let n = self.adj.len();
+        let mut cnt = 0;
+        let mut visited = Vec::with_capacity(n);
+
+        for root in 0..n {
+            if self.ord[root] != UNVISITED {
+                continue;
+            }
+
+            // (vertex, edge to its parent, next adjacency index)
+            let mut stack = vec![(root, UNVISITED, 0)];
+            let mut root_children = 0;
+
+            self.ord[root] = cnt;
+            self.low[root] = cnt;
+            cnt += 1;
+            visited.push(root);
+
+            while let Some(&mut (vertex, parent_edge, ref mut idx)) = stack.last_mut() {
+                if let Some(&(next, edge)) = self.adj[vertex].get(*idx) {
+                    *idx += 1;
+
+                    if edge == parent_edge {
+                        continue;
+                    }
+
+                    if self.ord[next] == UNVISITED {
+                        self.ord[next] = cnt;
+                        self.low[next] = cnt;
+                        cnt += 1;
+                        visited.push(next);
+                        stack.push((next, edge, 0));
+                    } else {
+                        self.low[vertex] = self.low[vertex].min(self.ord[next]);
+                    }
+
+                    continue;
+                }
+
+                stack.pop();
+
+                let Some(&(parent, _, _)) = stack.last() else {
+                    break;
+                };
+
+                self.low[parent] = self.low[parent].min(self.low[vertex]);
+
+                if self.low[vertex] > self.ord[parent] {
+                    self.bridges.push(parent_edge);
+                }
+
+                // parent separates the subtree of vertex, which closes a block
+                if self.low[vertex] >= self.ord[parent] {
+                    if parent == root {
+                        root_children += 1;
+                    } else {
+                        self.is_cut[parent] = true;
+                    }
+
+                    let mut block = vec![parent];
+
+                    loop {
+                        let top = visited.pop().unwrap();
+                        block.push(top);
+
+                        if top == vertex {
+                            break;
+                        }
+                    }
+
+                    self.blocks.push(block);
+                }
+            }
+
+            self.is_cut[root] = root_children >= 2;
+
+            if self.adj[root].iter().all(|&(next, _)| next == root) {
+                self.blocks.push(vec![root]);
+            }
+
+            visited.clear();
+        }
+    }
+
+    fn articulation_points(&self) -> Vec<usize> {
+        (0..self.adj.len()).filter(|&v| self.is_cut[v]).collect()
+    }
+}
+
+// Tree whose nodes 0..blocks are the blocks and the rest the cut vertices
+// A cut vertex is joined to every block containing it, node[v] is the node of vertex v,
+// its own node for a cut vertex and its only block otherwise
+struct BlockCutTree {
+    num_blocks: usize,
+    node: Vec<usize>,
+    adj: Vec<Vec<usize>>,
+}
+
+impl BlockCutTree {
+    fn new(low_link: &LowLink) -> Self {
+        let n = low_link.adj.len();
+        let num_blocks = low_link.blocks.len();
+        let mut node = vec![UNVISITED; n];
+        let mut adj = vec![Vec::new(); num_blocks];
+
+        for (v, &is_cut) in low_link.is_cut.iter().enumerate() {
+            if is_cut {
+                node[v] = adj.len();
+                adj.push(Vec::new());
+            }
+        }
+
+        for (idx, block) in low_link.blocks.iter().enumerate() {
+            for &v in block.iter() {
+                if low_link.is_cut[v] {
+                    adj[idx].push(node[v]);
+                    adj[node[v]].push(idx);
+                } else {
+                    node[v] = idx;
+                }
+            }
+        }
+
+        Self {
+            num_blocks,
+            node,
+            adj,
+        }
+    }
+}
@@ -0,0 +1,498 @@
+// Requires Mod Int.rs for Montgomery
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+const SMALL_PRIMES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+// n - 1 = d * 2^s, returns false if a proves n composite
+fn process_miller_rabin(mg: &Montgomery, a: u64, d: u64, s: u32) -> bool {
+    let n = mg.n;
+    let one = mg.encode(1);
+    let minus_one = mg.encode(n - 1);
+    let mut x = mg.pow(mg.encode(a), d);
+
+    if x == one || x == minus_one {
+        return true;
+    }
+
+    for _ in 1..s {
+        x = mg.mul(x, x);
+
+        if x == minus_one {
+            return true;
+        }
+    }
+
+    false
+}
+
+// Reference: https://miller-rabin.appspot.com/
+// Deterministic for every u64 with these seven bases
+fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+
+    for &p in SMALL_PRIMES.iter() {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    if n < 37 * 37 {
+        return true;
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let mg = Montgomery::new(n);
+
+    [2, 325, 9375, 28178, 450775, 9780504, 1795265022]
+        .iter()
+        .map(|&a| a % n)
+        .filter(|&a| a != 0)
+        .all(|a| process_miller_rabin(&mg, a, d, s))
+}
+
+// Reference: https://maths-people.anu.edu.au/~brent/pd/rpb051i.pdf
+// Nontrivial factor of an odd composite n
+fn pollard_rho(n: u64) -> u64 {
+    let mg = Montgomery::new(n);
+    let add = |a: u64, b: u64| {
+        let (sum, overflow) = a.overflowing_add(b);
+
+        if overflow || sum >= n {
+            sum.wrapping_sub(n)
+        } else {
+            sum
+        }
+    };
+    const BATCH: u64 = 128;
+
+    for c in 1.. {
+        let c = mg.encode(c);
+        let f = |x: u64| add(mg.mul(x, x), c);
+
+        let mut x = 0;
+        let mut y = mg.encode(2);
+        let mut ys = y;
+        let mut q = mg.encode(1);
+        let mut g = 1;
+        let mut r = 1;
+
+        while g == 1 {
+            x = y;
+
+            for _ in 0..r {
+                y = f(y);
+            }
+
+            let mut k = 0;
+
+            // Differences are multiplied together so that gcd runs once per batch
+            while k < r && g == 1 {
+                ys = y;
+
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(y);
+                    q = mg.mul(q, x.abs_diff(y));
+                }
+
+                g = gcd(q, n);
+                k += BATCH;
+            }
+
+            r *= 2;
+        }
+
+        // The batch overshot, so step through it one value at a time
+        if g == n {
+            loop {
+                ys = f(ys);
+                g = gcd(x.abs_diff(ys), n);
+
+                if g != 1 {
+                    break;
+                }
+            }
+        }
+
+        if g != n {
+            return g;
+        }
+    }
+
+    unreachable!()
+}
+
+fn record(n: u64, factors: &mut Vec<u64>) {
+    if n == 1 {
+        return;
+    }
+
+    if is_prime(n) {
+        factors.push(n);
+        return;
+    }
+
+    let d = pollard_rho(n);
+    record(d, factors);
+    record(n / d, factors);
+}
+
+// Sorted (prime, exponent) pairs, n must be positive
+fn factorize(mut n: u64) -> Vec<(u64, u32)> {
+    let mut factors = Vec::new();
+
+    for &p in SMALL_PRIMES.iter() {
+        while n.is_multiple_of(p) {
+            factors.push(p);
+            n /= p;
+        }
+    }
+
+    record(n, &mut factors);
+    factors.sort_unstable();
+
+    let mut ret: Vec<(u64, u32)> = Vec::new();
+
+    for p in factors {
+        match ret.last_mut() {
+            Some(last) if last.0 == p => last.1 += 1,
+            _ => ret.push((p, 1)),
+        }
+    }
+
+    ret
+}
+
+// Sorted divisors of n
+fn divisors(n: u64) -> Vec<u64> {
+    let mut ret = vec![1];
+
+    for (p, e) in factorize(n) {
+        let len = ret.len();
+        let mut piv = 1;
+
+        for _ in 0..e {
+            piv *= p;
+
+            for i in 0..len {
+                ret.push(ret[i] * piv);
+            }
+        }
+    }
+
+    ret.sort_unstable();
+    ret
+}
+
+fn euler_phi(n: u64) -> u64 {
+    factorize(n)
+        .iter()
+        .fold(n, |acc, &(p, _)| acc / p * (p - 1))
+}
+
+// Smallest m with a^m = 1 (mod n) for every a coprime to n
+fn carmichael_lambda(n: u64) -> u64 {
+    let mut ret = 1;
+
+    for (p, e) in factorize(n) {
+        let lambda = if p == 2 && e >= 3 {
+            1 << (e - 2)
+        } else {
+            p.pow(e - 1) * (p - 1)
+        };
+
+        ret = ret / gcd(ret, lambda) * lambda;
+    }
+
+    ret
+}
+
+fn pow_mod_u64(x: u64, mut y: u64, modular: u64) -> u64 {
+    let mut ret = 1 % modular;
+    let mut piv = x % modular;
+
+    while y > 0 {
+        if y & 1 != 0 {
+            ret = (ret as u128 * piv as u128 % modular as u128) as u64;
+        }
+
+        piv = (piv as u128 * piv as u128 % modular as u128) as u64;
+        y >>= 1;
+    }
+
+    ret
+}
+
+// Exists only for n = 1, 2, 4, p^k and 2p^k with an odd prime p
+fn smallest_primitive_root(n: u64) -> Option<u64> {
+    if n <= 4 {
+        return n.checked_sub(1);
+    }
+
+    let factors = factorize(n);
+    let odd = factors.iter().filter(|&&(p, _)| p != 2).count();
+    let cyclic = match factors[0] {
+        (2, 1) => odd == 1,
+        (2, _) => false,
+        _ => odd == 1,
+    };
+
+    if !cyclic {
+        return None;
+    }
+
+    let phi = euler_phi(n);
+    let phi_factors = factorize(phi);
+
+    (2..n).find(|&g| {
+        gcd(g, n) == 1
+            && phi_factors
+                .iter()
+                .all(|&(q, _)| pow_mod_u64(g, phi / q, n) != 1)
+    })
+}
+
+// Full 128 x 128 -> 256 bit product as (high, low)
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+
+    let (a1, a0) = (a >> 64, a & MASK);
+    let (b1, b0) = (b >> 64, b & MASK);
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let mid = (p00 >> 64) + (p01 & MASK) + (p10 & MASK);
+
+    (
+        a1 * b1 + (p01 >> 64) + (p10 >> 64) + (mid >> 64),
+        (p00 & MASK) | (mid << 64),
+    )
+}
+
+// Montgomery form for an odd u128 modulus with R = 2^128
+#[derive(Clone, Copy)]
+struct Montgomery128 {
+    n: u128,
+    n_inv: u128,
+    r2: u128,
+}
+
+impl Montgomery128 {
+    fn new(n: u128) -> Self {
+        assert!(!n.is_multiple_of(2));
+
+        let mut n_inv = n;
+
+        for _ in 0..6 {
+            n_inv = n_inv.wrapping_mul(2u128.wrapping_sub(n.wrapping_mul(n_inv)));
+        }
+
+        // R mod n, then doubled 128 times to get R^2 mod n
+        let mut r2 = (u128::MAX % n + 1) % n;
+
+        for _ in 0..128 {
+            r2 = if r2 >= n - r2 { r2 - (n - r2) } else { r2 + r2 };
+        }
+
+        Self { n, n_inv, r2 }
+    }
+
+    fn reduce(&self, (high, low): (u128, u128)) -> u128 {
+        let m = low.wrapping_mul(self.n_inv);
+        let mn = mul_wide(m, self.n).0;
+
+        if high >= mn {
+            high - mn
+        } else {
+            high.wrapping_sub(mn).wrapping_add(self.n)
+        }
+    }
+
+    fn encode(&self, a: u128) -> u128 {
+        self.reduce(mul_wide(a % self.n, self.r2))
+    }
+
+    fn mul(&self, a: u128, b: u128) -> u128 {
+        self.reduce(mul_wide(a, b))
+    }
+
+    fn pow(&self, mut base: u128, mut exp: u128) -> u128 {
+        let mut ret = self.encode(1);
+
+        while exp > 0 {
+            if exp & 1 != 0 {
+                ret = self.mul(ret, base);
+            }
+
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+
+        ret
+    }
+}
+
+fn gcd_u128(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+// Deterministic below 3.3 * 10^24, a strong probable prime test beyond that
+fn is_prime_u128(n: u128) -> bool {
+    if n <= u64::MAX as u128 {
+        return is_prime(n as u64);
+    }
+
+    if n.is_multiple_of(2) {
+        return false;
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let mg = Montgomery128::new(n);
+    let one = mg.encode(1);
+    let minus_one = mg.encode(n - 1);
+
+    [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41]
+        .iter()
+        .all(|&a| {
+            let mut x = mg.pow(mg.encode(a), d);
+
+            if x == one || x == minus_one {
+                return true;
+            }
+
+            for _ in 1..s {
+                x = mg.mul(x, x);
+
+                if x == minus_one {
+                    return true;
+                }
+            }
+
+            false
+        })
+}
+
+// Same as pollard_rho, takes about sqrt(p) steps for the smallest prime factor p
+fn pollard_rho_u128(n: u128) -> u128 {
+    let mg = Montgomery128::new(n);
+    let add = |a: u128, b: u128| {
+        let (sum, overflow) = a.overflowing_add(b);
+
+        if overflow || sum >= n {
+            sum.wrapping_sub(n)
+        } else {
+            sum
+        }
+    };
+    const BATCH: u128 = 128;
+
+    for c in 1.. {
+        let c = mg.encode(c);
+        let f = |x: u128| add(mg.mul(x, x), c);
+
+        let mut x = 0;
+        let mut y = mg.encode(2);
+        let mut ys = y;
+        let mut q = mg.encode(1);
+        let mut g = 1;
+        let mut r = 1;
+
+        while g == 1 {
+            x = y;
+
+            for _ in 0..r {
+                y = f(y);
+            }
+
+            let mut k = 0;
+
+            while k < r && g == 1 {
+                ys = y;
+
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(y);
+                    q = mg.mul(q, x.abs_diff(y));
+                }
+
+                g = gcd_u128(q, n);
+                k += BATCH;
+            }
+
+            r *= 2;
+        }
+
+        if g == n {
+            loop {
+                ys = f(ys);
+                g = gcd_u128(x.abs_diff(ys), n);
+
+                if g != 1 {
+                    break;
+                }
+            }
+        }
+
+        if g != n {
+            return g;
+        }
+    }
+
+    unreachable!()
+}
+
+fn record_u128(n: u128, factors: &mut Vec<u128>) {
+    if n <= u64::MAX as u128 {
+        let mut small = Vec::new();
+        record(n as u64, &mut small);
+        factors.extend(small.into_iter().map(|p| p as u128));
+        return;
+    }
+
+    if is_prime_u128(n) {
+        factors.push(n);
+        return;
+    }
+
+    let d = pollard_rho_u128(n);
+    record_u128(d, factors);
+    record_u128(n / d, factors);
+}
+
+fn factorize_u128(mut n: u128) -> Vec<(u128, u32)> {
+    let mut factors = Vec::new();
+
+    for &p in SMALL_PRIMES.iter() {
+        while n.is_multiple_of(p as u128) {
+            factors.push(p as u128);
+            n /= p as u128;
+        }
+    }
+
+    record_u128(n, &mut factors);
+    factors.sort_unstable();
+
+    let mut ret: Vec<(u128, u32)> = Vec::new();
+
+    for p in factors {
+        match ret.last_mut() {
+            Some(last) if last.0 == p => last.1 += 1,
+            _ => ret.push((p, 1)),
+        }
+    }
+
+    ret
+}
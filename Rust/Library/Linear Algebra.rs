@@ -0,0 +1,603 @@
+// Requires Mod Int.rs for ModInt and Big Decimal.rs for BigRational
+// Drop the Field impl of whichever of the two is not pasted
+
+// Exact arithmetic only, a value is treated as zero when it equals zero()
+trait Field: Clone + PartialEq {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn add(&self, other: &Self) -> Self;
+    fn sub(&self, other: &Self) -> Self;
+    fn mul(&self, other: &Self) -> Self;
+
+    // Only called on nonzero values
+    fn inv(&self) -> Self;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+}
+
+impl<const M: u32> Field for ModInt<M> {
+    fn zero() -> Self {
+        Self::raw(0)
+    }
+
+    fn one() -> Self {
+        Self::raw(1 % M)
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        *self + *other
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        *self - *other
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        *self * *other
+    }
+
+    fn inv(&self) -> Self {
+        ModInt::inv(*self)
+    }
+}
+
+impl Field for DynamicModInt {
+    fn zero() -> Self {
+        Self::raw(0)
+    }
+
+    fn one() -> Self {
+        Self::new(1)
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        *self + *other
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        *self - *other
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        *self * *other
+    }
+
+    fn inv(&self) -> Self {
+        DynamicModInt::inv(*self)
+    }
+}
+
+impl Field for BigRational {
+    fn zero() -> Self {
+        Self::from(0)
+    }
+
+    fn one() -> Self {
+        Self::from(1)
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        self + other
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        self - other
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        self * other
+    }
+
+    fn inv(&self) -> Self {
+        self.recip()
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct Matrix<T: Field> {
+    n: usize,
+    m: usize,
+    data: Vec<Vec<T>>,
+}
+
+impl<T: Field> Matrix<T> {
+    fn new(n: usize, m: usize) -> Self {
+        Self {
+            n,
+            m,
+            data: vec![vec![T::zero(); m]; n],
+        }
+    }
+
+    fn identity(n: usize) -> Self {
+        let mut ret = Self::new(n, n);
+
+        for i in 0..n {
+            ret.data[i][i] = T::one();
+        }
+
+        ret
+    }
+
+    fn from_rows(data: Vec<Vec<T>>) -> Self {
+        let n = data.len();
+        let m = data.first().map_or(0, |row| row.len());
+
+        assert!(
+            data.iter().all(|row| row.len() == m),
+            "Rows differ in length"
+        );
+
+        Self { n, m, data }
+    }
+
+    fn pow(&self, mut exp: u64) -> Self {
+        assert!(self.n == self.m, "Matrix is not square");
+
+        let mut ret = Self::identity(self.n);
+        let mut piv = self.clone();
+
+        while exp > 0 {
+            if exp & 1 != 0 {
+                ret = &ret * &piv;
+            }
+
+            piv = &piv * &piv;
+            exp >>= 1;
+        }
+
+        ret
+    }
+
+    // Gauss-Jordan elimination into reduced row echelon form, pivots are chosen among
+    // the first `cols` columns only so that augmented columns are carried along
+    // Returns the pivot columns and the product of the pivots with the sign of the swaps
+    fn row_reduce(&mut self, cols: usize) -> (Vec<usize>, T) {
+        let mut pivots = Vec::new();
+        let mut det = T::one();
+
+        for c in 0..cols {
+            let r = pivots.len();
+            let p = match (r..self.n).find(|&i| !self.data[i][c].is_zero()) {
+                Some(p) => p,
+                None => continue,
+            };
+
+            if p != r {
+                self.data.swap(p, r);
+                det = T::zero().sub(&det);
+            }
+
+            det = det.mul(&self.data[r][c]);
+
+            let inv = self.data[r][c].inv();
+
+            for x in self.data[r].iter_mut() {
+                *x = x.mul(&inv);
+            }
+
+            let pivot = self.data[r].clone();
+
+            for i in 0..self.n {
+                if i == r || self.data[i][c].is_zero() {
+                    continue;
+                }
+
+                let factor = self.data[i][c].clone();
+
+                for (x, y) in self.data[i].iter_mut().zip(pivot.iter()).skip(c) {
+                    *x = x.sub(&factor.mul(y));
+                }
+            }
+
+            pivots.push(c);
+
+            if pivots.len() == self.n {
+                break;
+            }
+        }
+
+        (pivots, det)
+    }
+
+    fn rank(&self) -> usize {
+        self.clone().row_reduce(self.m).0.len()
+    }
+
+    fn determinant(&self) -> T {
+        assert!(self.n == self.m, "Matrix is not square");
+
+        let (pivots, det) = self.clone().row_reduce(self.m);
+
+        if pivots.len() == self.n {
+            det
+        } else {
+            T::zero()
+        }
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        assert!(self.n == self.m, "Matrix is not square");
+
+        let n = self.n;
+        let mut aug = Self::new(n, 2 * n);
+
+        for i in 0..n {
+            aug.data[i][..n].clone_from_slice(&self.data[i]);
+            aug.data[i][n + i] = T::one();
+        }
+
+        if aug.row_reduce(n).0.len() < n {
+            return None;
+        }
+
+        Some(Self::from_rows(
+            aug.data.into_iter().map(|row| row[n..].to_vec()).collect(),
+        ))
+    }
+
+    // Basis of {x | Ax = 0} read off a reduced matrix, one vector per free column
+    fn kernel_of(reduced: &Self, pivots: &[usize], m: usize) -> Vec<Vec<T>> {
+        let mut is_pivot = vec![false; m];
+
+        for &c in pivots.iter() {
+            is_pivot[c] = true;
+        }
+
+        (0..m)
+            .filter(|&f| !is_pivot[f])
+            .map(|f| {
+                let mut v = vec![T::zero(); m];
+                v[f] = T::one();
+
+                for (i, &c) in pivots.iter().enumerate() {
+                    v[c] = T::zero().sub(&reduced.data[i][f]);
+                }
+
+                v
+            })
+            .collect()
+    }
+
+    fn kernel(&self) -> Vec<Vec<T>> {
+        let mut reduced = self.clone();
+        let (pivots, _) = reduced.row_reduce(self.m);
+
+        Self::kernel_of(&reduced, &pivots, self.m)
+    }
+
+    // Ax = b as one solution with every free variable at zero, plus a kernel basis
+    // Every solution is the first plus any combination of the others
+    fn solve(&self, b: &[T]) -> Option<(Vec<T>, Vec<Vec<T>>)> {
+        assert!(b.len() == self.n, "Right-hand side has the wrong length");
+
+        let m = self.m;
+        let mut aug = Self::new(self.n, m + 1);
+
+        for ((row, src), val) in aug.data.iter_mut().zip(self.data.iter()).zip(b.iter()) {
+            row[..m].clone_from_slice(src);
+            row[m] = val.clone();
+        }
+
+        let (pivots, _) = aug.row_reduce(m);
+
+        if aug.data[pivots.len()..].iter().any(|row| !row[m].is_zero()) {
+            return None;
+        }
+
+        let mut x = vec![T::zero(); m];
+
+        for (i, &c) in pivots.iter().enumerate() {
+            x[c] = aug.data[i][m].clone();
+        }
+
+        Some((x, Self::kernel_of(&aug, &pivots, m)))
+    }
+}
+
+impl<T: Field> std::ops::Mul for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, other: &Matrix<T>) -> Matrix<T> {
+        assert!(self.m == other.n, "Dimension mismatch");
+
+        let mut ret = Matrix::<T>::new(self.n, other.m);
+
+        for i in 0..self.n {
+            for k in 0..self.m {
+                if self.data[i][k].is_zero() {
+                    continue;
+                }
+
+                for j in 0..other.m {
+                    ret.data[i][j] = ret.data[i][j].add(&self.data[i][k].mul(&other.data[k][j]));
+                }
+            }
+        }
+
+        ret
+    }
+}
+
+// Matrix over GF(2) with rows packed into u64 words, vectors are bitsets of the same layout
+#[derive(Clone, PartialEq, Debug)]
+struct BitMatrix {
+    n: usize,
+    m: usize,
+    rows: Vec<Vec<u64>>,
+}
+
+// One spare word keeps an empty matrix valid without a division that rounds up
+fn bitset_words(bits: usize) -> usize {
+    bits / 64 + 1
+}
+
+fn bitset_get(v: &[u64], i: usize) -> bool {
+    (v[i / 64] >> (i % 64)) & 1 != 0
+}
+
+fn bitset_set(v: &mut [u64], i: usize, val: bool) {
+    if val {
+        v[i / 64] |= 1 << (i % 64);
+    } else {
+        v[i / 64] &= !(1 << (i % 64));
+    }
+}
+
+impl BitMatrix {
+    fn new(n: usize, m: usize) -> Self {
+        Self {
+            n,
+            m,
+            rows: vec![vec![0; bitset_words(m)]; n],
+        }
+    }
+
+    fn identity(n: usize) -> Self {
+        let mut ret = Self::new(n, n);
+
+        for i in 0..n {
+            ret.set(i, i, true);
+        }
+
+        ret
+    }
+
+    fn get(&self, i: usize, j: usize) -> bool {
+        bitset_get(&self.rows[i], j)
+    }
+
+    fn set(&mut self, i: usize, j: usize, val: bool) {
+        bitset_set(&mut self.rows[i], j, val);
+    }
+
+    // Same as Matrix::row_reduce, in O(n * m * rank / 64)
+    fn row_reduce(&mut self, cols: usize) -> Vec<usize> {
+        let mut pivots = Vec::new();
+
+        for c in 0..cols {
+            let r = pivots.len();
+            let p = match (r..self.n).find(|&i| self.get(i, c)) {
+                Some(p) => p,
+                None => continue,
+            };
+
+            self.rows.swap(p, r);
+
+            let pivot = self.rows[r].clone();
+
+            for i in 0..self.n {
+                if i != r && self.get(i, c) {
+                    for (x, y) in self.rows[i].iter_mut().zip(pivot.iter()).skip(c / 64) {
+                        *x ^= y;
+                    }
+                }
+            }
+
+            pivots.push(c);
+
+            if pivots.len() == self.n {
+                break;
+            }
+        }
+
+        pivots
+    }
+
+    fn rank(&self) -> usize {
+        self.clone().row_reduce(self.m).len()
+    }
+
+    fn determinant(&self) -> bool {
+        assert!(self.n == self.m, "Matrix is not square");
+        self.rank() == self.n
+    }
+
+    // Copy with `extra` zero columns appended on the right
+    fn widened(&self, extra: usize) -> Self {
+        let mut ret = Self::new(self.n, self.m + extra);
+
+        for (dst, src) in ret.rows.iter_mut().zip(self.rows.iter()) {
+            dst[..src.len()].copy_from_slice(src);
+        }
+
+        ret
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        assert!(self.n == self.m, "Matrix is not square");
+
+        let n = self.n;
+        let mut aug = self.widened(n);
+
+        for i in 0..n {
+            aug.set(i, n + i, true);
+        }
+
+        if aug.row_reduce(n).len() < n {
+            return None;
+        }
+
+        let mut ret = Self::new(n, n);
+
+        for i in 0..n {
+            for j in 0..n {
+                ret.set(i, j, aug.get(i, n + j));
+            }
+        }
+
+        Some(ret)
+    }
+
+    fn kernel_of(reduced: &Self, pivots: &[usize], m: usize) -> Vec<Vec<u64>> {
+        let mut is_pivot = vec![false; m];
+
+        for &c in pivots.iter() {
+            is_pivot[c] = true;
+        }
+
+        (0..m)
+            .filter(|&f| !is_pivot[f])
+            .map(|f| {
+                let mut v = vec![0; bitset_words(m)];
+                bitset_set(&mut v, f, true);
+
+                for (i, &c) in pivots.iter().enumerate() {
+                    bitset_set(&mut v, c, reduced.get(i, f));
+                }
+
+                v
+            })
+            .collect()
+    }
+
+    fn kernel(&self) -> Vec<Vec<u64>> {
+        let mut reduced = self.clone();
+        let pivots = reduced.row_reduce(self.m);
+
+        Self::kernel_of(&reduced, &pivots, self.m)
+    }
+
+    // b holds n bits, the solution and the kernel vectors hold m bits each
+    fn solve(&self, b: &[u64]) -> Option<(Vec<u64>, Vec<Vec<u64>>)> {
+        let m = self.m;
+        let mut aug = self.widened(1);
+
+        for i in 0..self.n {
+            aug.set(i, m, bitset_get(b, i));
+        }
+
+        let pivots = aug.row_reduce(m);
+
+        if (pivots.len()..self.n).any(|i| aug.get(i, m)) {
+            return None;
+        }
+
+        let mut x = vec![0; bitset_words(m)];
+
+        for (i, &c) in pivots.iter().enumerate() {
+            bitset_set(&mut x, c, aug.get(i, m));
+        }
+
+        Some((x, Self::kernel_of(&aug, &pivots, m)))
+    }
+}
+
+// Reference: https://codeforces.com/blog/entry/68953
+// basis[b] is the vector whose highest set bit is b, if any
+#[derive(Clone)]
+struct XorBasis {
+    basis: [u64; 64],
+    len: usize,
+}
+
+impl XorBasis {
+    fn new() -> Self {
+        Self {
+            basis: [0; 64],
+            len: 0,
+        }
+    }
+
+    // Returns false if x was already in the span
+    fn insert(&mut self, mut x: u64) -> bool {
+        for b in (0..64).rev() {
+            if (x >> b) & 1 == 0 {
+                continue;
+            }
+
+            if self.basis[b] == 0 {
+                self.basis[b] = x;
+                self.len += 1;
+                return true;
+            }
+
+            x ^= self.basis[b];
+        }
+
+        false
+    }
+
+    fn contains(&self, mut x: u64) -> bool {
+        for b in (0..64).rev() {
+            if (x >> b) & 1 != 0 {
+                x ^= self.basis[b];
+            }
+        }
+
+        x == 0
+    }
+
+    // Largest x ^ v over every v in the span
+    fn max_xor(&self, x: u64) -> u64 {
+        self.basis.iter().rev().fold(x, |acc, &v| acc.max(acc ^ v))
+    }
+
+    fn min_xor(&self, x: u64) -> u64 {
+        self.basis.iter().rev().fold(x, |acc, &v| acc.min(acc ^ v))
+    }
+
+    fn merge(&mut self, other: &Self) {
+        for &v in other.basis.iter() {
+            if v != 0 {
+                self.insert(v);
+            }
+        }
+    }
+
+    // k-th smallest value of the span counting 0 as the 0-th, which has 2^len values
+    fn kth(&self, mut k: u64) -> Option<u64> {
+        if self.len < 64 && k >> self.len != 0 {
+            return None;
+        }
+
+        // Clearing every leading bit from the other vectors makes bit i of k pick vector i
+        let mut reduced = self.basis;
+
+        for b in 0..64 {
+            if reduced[b] == 0 {
+                continue;
+            }
+
+            for c in b + 1..64 {
+                if (reduced[c] >> b) & 1 != 0 {
+                    reduced[c] ^= reduced[b];
+                }
+            }
+        }
+
+        let mut ret = 0;
+
+        for &v in reduced.iter().filter(|&&v| v != 0) {
+            if k & 1 != 0 {
+                ret ^= v;
+            }
+
+            k >>= 1;
+        }
+
+        Some(ret)
+    }
+}
@@ -0,0 +1,377 @@
+// Requires Linear Algebra.rs for Field, exact mode also needs Big Decimal.rs for BigRational
+
+// Comparisons go through eps, which is zero for exact types
+trait OrderedField: Field + PartialOrd {
+    fn eps() -> Self;
+    fn floor(&self) -> Self;
+
+    fn positive(&self) -> bool {
+        *self > Self::eps()
+    }
+
+    fn negative(&self) -> bool {
+        *self < Self::zero().sub(&Self::eps())
+    }
+
+    fn abs(&self) -> Self {
+        if *self < Self::zero() {
+            Self::zero().sub(self)
+        } else {
+            self.clone()
+        }
+    }
+}
+
+impl Field for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn one() -> Self {
+        1.0
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        self + other
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        self - other
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        self * other
+    }
+
+    fn inv(&self) -> Self {
+        1.0 / self
+    }
+}
+
+impl OrderedField for f64 {
+    fn eps() -> Self {
+        1e-9
+    }
+
+    fn floor(&self) -> Self {
+        f64::floor(*self)
+    }
+}
+
+impl OrderedField for BigRational {
+    fn eps() -> Self {
+        Self::from(0)
+    }
+
+    fn floor(&self) -> Self {
+        Self::from(BigRational::floor(self))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum LPResult<T> {
+    // dual[i] is the shadow price of constraint i, so b * dual equals value at the optimum
+    Optimal { value: T, x: Vec<T>, dual: Vec<T> },
+    Infeasible,
+    Unbounded,
+}
+
+const ARTIFICIAL: usize = usize::MAX;
+
+// Reference: https://github.com/jaehyunp/stanfordacm/blob/master/code/Simplex.cc
+// Maximizes c * x subject to Ax <= b and x >= 0 with a two-phase simplex
+// Variables 0..n are the inputs, n..n + m the slacks of each constraint
+struct LPSolver<T: OrderedField> {
+    m: usize,
+    n: usize,
+    basic: Vec<usize>,
+    non_basic: Vec<usize>,
+    tableau: Vec<Vec<T>>,
+}
+
+impl<T: OrderedField> LPSolver<T> {
+    fn new(a: &[Vec<T>], b: &[T], c: &[T]) -> Self {
+        let m = b.len();
+        let n = c.len();
+        let mut tableau = vec![vec![T::zero(); n + 2]; m + 2];
+
+        for i in 0..m {
+            tableau[i][..n].clone_from_slice(&a[i]);
+            tableau[i][n] = T::zero().sub(&T::one());
+            tableau[i][n + 1] = b[i].clone();
+        }
+
+        for j in 0..n {
+            tableau[m][j] = T::zero().sub(&c[j]);
+        }
+
+        tableau[m + 1][n] = T::one();
+
+        let mut non_basic = (0..n).collect::<Vec<_>>();
+        non_basic.push(ARTIFICIAL);
+
+        Self {
+            m,
+            n,
+            basic: (n..n + m).collect(),
+            non_basic,
+            tableau,
+        }
+    }
+
+    fn pivot(&mut self, r: usize, s: usize) {
+        let inv = self.tableau[r][s].inv();
+        let row = self.tableau[r].clone();
+
+        for i in 0..self.m + 2 {
+            if i == r || self.tableau[i][s].is_zero() {
+                continue;
+            }
+
+            let factor = self.tableau[i][s].mul(&inv);
+
+            for (x, y) in self.tableau[i].iter_mut().zip(row.iter()) {
+                *x = x.sub(&y.mul(&factor));
+            }
+
+            self.tableau[i][s] = T::zero().sub(&factor);
+        }
+
+        for (j, x) in self.tableau[r].iter_mut().enumerate() {
+            *x = if j == s { inv.clone() } else { x.mul(&inv) };
+        }
+
+        std::mem::swap(&mut self.basic[r], &mut self.non_basic[s]);
+    }
+
+    // Returns false if the objective in row m (phase 1) or m + 1 (phase 2) is unbounded
+    // Dantzig's rule is fast in practice, Bland's rule takes over on a long degenerate streak
+    // because it cannot cycle
+    fn simplex(&mut self, phase: usize) -> bool {
+        let (m, n) = (self.m, self.n);
+        let x = m + phase - 1;
+        let mut degenerate = 0;
+        let mut bland = false;
+
+        loop {
+            let mut s: Option<usize> = None;
+
+            for j in 0..=n {
+                if (phase == 1 && self.non_basic[j] == ARTIFICIAL) || !self.tableau[x][j].negative()
+                {
+                    continue;
+                }
+
+                let better = match s {
+                    None => true,
+                    Some(t) if bland => self.non_basic[j] < self.non_basic[t],
+                    Some(t) => {
+                        self.tableau[x][j] < self.tableau[x][t]
+                            || (self.tableau[x][j] == self.tableau[x][t]
+                                && self.non_basic[j] < self.non_basic[t])
+                    }
+                };
+
+                if better {
+                    s = Some(j);
+                }
+            }
+
+            let s = match s {
+                Some(s) => s,
+                None => return true,
+            };
+
+            let mut r: Option<usize> = None;
+
+            for i in 0..m {
+                if !self.tableau[i][s].positive() {
+                    continue;
+                }
+
+                // Ratios compared by cross multiplication, both denominators are positive
+                let better = match r {
+                    None => true,
+                    Some(t) => {
+                        let lhs = self.tableau[i][n + 1].mul(&self.tableau[t][s]);
+                        let rhs = self.tableau[t][n + 1].mul(&self.tableau[i][s]);
+
+                        lhs < rhs || (lhs == rhs && self.basic[i] < self.basic[t])
+                    }
+                };
+
+                if better {
+                    r = Some(i);
+                }
+            }
+
+            let r = match r {
+                Some(r) => r,
+                None => return false,
+            };
+
+            if self.tableau[r][n + 1].positive() {
+                degenerate = 0;
+            } else {
+                degenerate += 1;
+                bland |= degenerate > m + n;
+            }
+
+            self.pivot(r, s);
+        }
+    }
+
+    fn solve(&mut self) -> LPResult<T> {
+        let (m, n) = (self.m, self.n);
+
+        // Phase 2 finds a feasible basis with the artificial variable when some b is negative
+        if let Some(r) = (0..m).min_by(|&i, &j| {
+            self.tableau[i][n + 1]
+                .partial_cmp(&self.tableau[j][n + 1])
+                .unwrap()
+        }) {
+            if self.tableau[r][n + 1].negative() {
+                self.pivot(r, n);
+
+                if !self.simplex(2) || self.tableau[m + 1][n + 1].negative() {
+                    return LPResult::Infeasible;
+                }
+
+                for i in 0..m {
+                    if self.basic[i] != ARTIFICIAL {
+                        continue;
+                    }
+
+                    let row = &self.tableau[i];
+                    let s = (0..=n)
+                        .filter(|&j| self.non_basic[j] != ARTIFICIAL)
+                        .max_by(|&j, &k| row[j].abs().partial_cmp(&row[k].abs()).unwrap());
+
+                    // An all-zero row is a redundant constraint and keeps the artificial at zero
+                    if let Some(s) = s.filter(|&s| row[s].abs().positive()) {
+                        self.pivot(i, s);
+                    }
+                }
+            }
+        }
+
+        if !self.simplex(1) {
+            return LPResult::Unbounded;
+        }
+
+        let mut x = vec![T::zero(); n];
+        let mut dual = vec![T::zero(); m];
+
+        for i in 0..m {
+            if self.basic[i] < n {
+                x[self.basic[i]] = self.tableau[i][n + 1].clone();
+            }
+        }
+
+        for j in 0..=n {
+            if self.non_basic[j] >= n && self.non_basic[j] != ARTIFICIAL {
+                dual[self.non_basic[j] - n] = self.tableau[m][j].clone();
+            }
+        }
+
+        LPResult::Optimal {
+            value: self.tableau[m][n + 1].clone(),
+            x,
+            dual,
+        }
+    }
+}
+
+// Returns false once some relaxation turns out unbounded
+fn process_branch_and_bound<T: OrderedField>(
+    a: &mut Vec<Vec<T>>,
+    b: &mut Vec<T>,
+    c: &[T],
+    integer: &[bool],
+    best: &mut Option<(T, Vec<T>)>,
+) -> bool {
+    let (value, mut x) = match LPSolver::new(a, b, c).solve() {
+        LPResult::Optimal { value, x, .. } => (value, x),
+        LPResult::Infeasible => return true,
+        LPResult::Unbounded => return false,
+    };
+
+    // The relaxation bounds every integral point below this node
+    if let Some((incumbent, _)) = best {
+        if !value.sub(incumbent).positive() {
+            return true;
+        }
+    }
+
+    let fractional = (0..c.len()).find(|&j| {
+        let frac = x[j].sub(&x[j].floor());
+        integer[j] && frac.positive() && T::one().sub(&frac).positive()
+    });
+
+    let j = match fractional {
+        Some(j) => j,
+        None => {
+            let half = T::one().add(&T::one()).inv();
+
+            for j in (0..c.len()).filter(|&j| integer[j]) {
+                x[j] = x[j].add(&half).floor();
+            }
+
+            *best = Some((value, x));
+            return true;
+        }
+    };
+
+    // Branch on x[j] <= floor and x[j] >= floor + 1
+    let floor = x[j].floor();
+    let mut row = vec![T::zero(); c.len()];
+
+    for (coef, bound) in [
+        (T::one(), floor.clone()),
+        (
+            T::zero().sub(&T::one()),
+            T::zero().sub(&floor.add(&T::one())),
+        ),
+    ] {
+        row[j] = coef;
+        a.push(row.clone());
+        b.push(bound);
+
+        let bounded = process_branch_and_bound(a, b, c, integer, best);
+
+        a.pop();
+        b.pop();
+
+        if !bounded {
+            return false;
+        }
+    }
+
+    true
+}
+
+// Same problem as LPSolver with x[j] integral wherever integer[j], for small instances
+// An unbounded relaxation is reported as unbounded and dual is left empty
+fn branch_and_bound<T: OrderedField>(
+    a: &[Vec<T>],
+    b: &[T],
+    c: &[T],
+    integer: &[bool],
+) -> LPResult<T> {
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    let mut best = None;
+
+    if !process_branch_and_bound(&mut a, &mut b, c, integer, &mut best) {
+        return LPResult::Unbounded;
+    }
+
+    match best {
+        Some((value, x)) => LPResult::Optimal {
+            value,
+            x,
+            dual: Vec::new(),
+        },
+        None => LPResult::Infeasible,
+    }
+}
@@ -0,0 +1,380 @@
+// Requires Convolution.rs for MOD, PRIME1 to PRIME3, garner, process_ntt and convolutions
+
+fn pow(x: i64, mut p: i64, modulo: i64) -> i64 {
+    let mut ret = 1;
+    let mut piv = x % modulo;
+
+    while p != 0 {
+        if p & 1 != 0 {
+            ret = ret * piv % modulo;
+        }
+
+        piv = piv * piv % modulo;
+        p >>= 1;
+    }
+
+    ret
+}
+
+// Reference: https://koosaga.com/231
+// Shortest rec with vals[i] = sum rec[j] * vals[i - j - 1], modulo must be prime
+fn berlekamp_massey(vals: &[i64], modulo: i64) -> Vec<i64> {
+    let mut ls = Vec::new();
+    let mut cur = Vec::new();
+
+    let mut lf = 0;
+    let mut ld = 0;
+
+    for i in 0..vals.len() {
+        let mut t = 0;
+
+        for j in 0..cur.len() {
+            t = (t + vals[i - j - 1] * cur[j]) % modulo;
+        }
+
+        if (t - vals[i]) % modulo == 0 {
+            continue;
+        }
+
+        if cur.is_empty() {
+            cur.resize(i + 1, 0);
+            lf = i;
+            ld = (t - vals[i]) % modulo;
+
+            continue;
+        }
+
+        let k = -(vals[i] - t) * pow(ld, modulo - 2, modulo) % modulo;
+
+        let mut c = vec![0; i - lf - 1];
+        c.push(k);
+
+        for j in ls.iter() {
+            c.push(-j * k % modulo);
+        }
+
+        if c.len() < cur.len() {
+            c.resize(cur.len(), 0);
+        }
+
+        for j in 0..cur.len() {
+            c[j] = (c[j] + cur[j]) % modulo;
+        }
+
+        if i - lf + ls.len() >= cur.len() {
+            (ls, lf, ld) = (cur, i, (t - vals[i]) % modulo);
+        }
+
+        cur = c;
+    }
+
+    for i in cur.iter_mut() {
+        *i = (*i % modulo + modulo) % modulo;
+    }
+
+    cur
+}
+
+// Kitamasa in O(k^2 log n), works for any modulo below 2^31
+fn get_nth(rec: &[i64], dp: &[i64], mut n: u64, modulo: i64) -> i64 {
+    let m = rec.len();
+
+    if m == 0 {
+        return 0;
+    }
+
+    let mut s = vec![0; m];
+    let mut t = vec![0; m];
+
+    s[0] = 1;
+    if m != 1 {
+        t[1] = 1;
+    } else {
+        t[0] = rec[0] % modulo;
+    }
+
+    let mul = |v: &[i64], w: &[i64]| -> Vec<i64> {
+        let mut t = vec![0; 2 * m];
+
+        for j in 0..m {
+            for k in 0..m {
+                t[j + k] = (t[j + k] + v[j] * w[k]) % modulo;
+            }
+        }
+
+        for j in (m..2 * m).rev() {
+            for k in 1..=m {
+                t[j - k] = (t[j - k] + t[j] * rec[k - 1]) % modulo;
+            }
+        }
+
+        t.truncate(m);
+        t
+    };
+
+    while n != 0 {
+        if n & 1 != 0 {
+            s = mul(&s, &t);
+        }
+
+        t = mul(&t, &t);
+        n >>= 1;
+    }
+
+    let mut ret = 0;
+
+    for i in 0..m {
+        ret = (ret + s[i] * dp[i]) % modulo;
+    }
+
+    (ret + modulo) % modulo
+}
+
+// One halving of Bostan-Mori modulo an NTT prime, returns the parity part of p(x) q(-x)
+// and the even part of q(x) q(-x), both halved in degree
+// q(-x) costs nothing since q(-w^i) = q(w^(i + len / 2)), and the parts are split before
+// the inverse transforms, so a step takes two NTTs of length len and two of half that
+fn bostan_mori_step(p: &[u32], q: &[u32], odd: bool, modular: u32) -> (Vec<u32>, Vec<u32>) {
+    let m = modular as u64;
+    let len_u = p.len() + q.len() - 1;
+    let len = len_u.max(2 * q.len() - 1).next_power_of_two().max(2);
+    let half = len / 2;
+    let mut fp = p.to_vec();
+    let mut fq = q.to_vec();
+
+    fp.resize(len, 0);
+    fq.resize(len, 0);
+
+    process_ntt(&mut fp, false, modular);
+    process_ntt(&mut fq, false, modular);
+
+    let inv_2 = m / 2 + 1;
+    let root = pow_mod(ntt_root(modular) as u64, (m - 1) / len as u64, m);
+    let inv_root = pow_mod(root, m - 2, m);
+    let mut scale = if odd { inv_2 } else { 0 };
+    let mut u = vec![0; half];
+    let mut v = vec![0; half];
+
+    for i in 0..half {
+        let j = i + half;
+        let ui = fp[i] as u64 * fq[j] as u64 % m;
+        let uj = fp[j] as u64 * fq[i] as u64 % m;
+
+        // u_even(w^2i) = (u(w^i) + u(-w^i)) / 2, u_odd(w^2i) = (u(w^i) - u(-w^i)) / 2w^i
+        u[i] = if odd {
+            let ret = (ui + m - uj) % m * scale % m;
+            scale = scale * inv_root % m;
+            ret
+        } else {
+            (ui + uj) % m * inv_2 % m
+        } as u32;
+        v[i] = (fq[i] as u64 * fq[j] as u64 % m) as u32;
+    }
+
+    process_ntt(&mut u, true, modular);
+    process_ntt(&mut v, true, modular);
+
+    u.truncate((len_u - odd as usize).div_ceil(2));
+    v.truncate(q.len());
+
+    (u, v)
+}
+
+// Same halving for any modulo below 2^31 through three NTT primes and Garner's algorithm
+// Exact while k * modulo^2 stays below PRIME1 * PRIME2 * PRIME3 / 2, so for k up to 6 * 10^6
+fn bostan_mori_step_arbitrary(
+    p: &[u32],
+    q: &[u32],
+    odd: bool,
+    modulo: u32,
+) -> (Vec<u32>, Vec<u32>) {
+    let reduce = |arr: &[u32], prime: u64| {
+        arr.iter()
+            .map(|&x| (x as u64 % prime) as u32)
+            .collect::<Vec<_>>()
+    };
+    let [s1, s2, s3] = [PRIME1, PRIME2, PRIME3]
+        .map(|prime| bostan_mori_step(&reduce(p, prime), &reduce(q, prime), odd, prime as u32));
+
+    // The coefficients of q(-x) are negated, so values past half the product are negative
+    let product = (PRIME1 * PRIME2) as u128 * PRIME3 as u128;
+    let md = modulo as u128;
+    let combine = |a: &[u32], b: &[u32], c: &[u32]| {
+        (0..a.len())
+            .map(|i| {
+                let x = garner(a[i] as u64, b[i] as u64, c[i] as u64);
+
+                if x > product / 2 {
+                    ((x % md + md - product % md) % md) as u32
+                } else {
+                    (x % md) as u32
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    (combine(&s1.0, &s2.0, &s3.0), combine(&s1.1, &s2.1, &s3.1))
+}
+
+// Reference: https://arxiv.org/abs/2008.08822
+// [x^n] p(x) / q(x) with q[0] = 1 in O(k log k log n)
+// NTT is used for 998244353, three NTT primes for any other modulo below 2^31
+fn bostan_mori(p: &[u32], q: &[u32], mut n: u64, modulo: u32) -> u32 {
+    let mut p = p.to_vec();
+    let mut q = q.to_vec();
+
+    while n > 0 && !p.is_empty() {
+        let odd = n % 2 == 1;
+
+        (p, q) = if modulo == MOD {
+            bostan_mori_step(&p, &q, odd, MOD)
+        } else {
+            bostan_mori_step_arbitrary(&p, &q, odd, modulo)
+        };
+        n /= 2;
+    }
+
+    p.first().copied().unwrap_or(0)
+}
+
+// n-th term of vals[i] = sum rec[j] * vals[i - j - 1] through Bostan-Mori
+fn get_nth_fast(rec: &[i64], dp: &[i64], n: u64, modulo: i64) -> i64 {
+    let k = rec.len();
+
+    if k == 0 {
+        return 0;
+    }
+
+    let normalize = |x: i64| ((x % modulo + modulo) % modulo) as u32;
+    let mut q = vec![1];
+    q.extend(rec.iter().map(|&c| normalize(-c)));
+
+    let dp = dp[..k].iter().map(|&x| normalize(x)).collect::<Vec<_>>();
+    let mut p = if modulo as u32 == MOD {
+        convolution_mod(&dp, &q, MOD)
+    } else {
+        convolution_arbitrary_mod(&dp, &q, modulo as u32)
+    };
+    p.truncate(k);
+
+    bostan_mori(&p, &q, n, modulo as u32) as i64
+}
+
+fn guess_nth_term(vals: &[i64], n: u64, modulo: i64) -> i64 {
+    if n < vals.len() as u64 {
+        return vals[n as usize] % modulo;
+    }
+
+    let rec = berlekamp_massey(vals, modulo);
+
+    if rec.is_empty() {
+        0
+    } else {
+        get_nth_fast(&rec, vals, n, modulo)
+    }
+}
+
+// Xorshift seeded by the hardware generator, named apart from Rng in Rolling Hash.rs
+struct XorShift(u64);
+
+impl XorShift {
+    fn new() -> Self {
+        let mut seed = 0;
+        unsafe { std::arch::x86_64::_rdrand64_step(&mut seed) };
+
+        Self(seed | 1)
+    }
+
+    fn next(&mut self, n: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % n
+    }
+}
+
+// Row-major sparse matrix given as (row, col, val) entries
+fn sparse_multiply(n: usize, entries: &[(usize, usize, i64)], v: &[i64], modulo: i64) -> Vec<i64> {
+    let mut ret = vec![0; n];
+
+    for &(row, col, val) in entries {
+        ret[row] = (ret[row] + val * v[col]) % modulo;
+    }
+
+    ret
+}
+
+// Reference: https://en.wikipedia.org/wiki/Block_Wiedemann_algorithm
+// Minimal polynomial of the sparse matrix with high probability, coef[i] is the coefficient of x^i
+// Runs in O(n * (n + nnz)), modulo must be a large prime
+fn sparse_minimal_polynomial(n: usize, entries: &[(usize, usize, i64)], modulo: i64) -> Vec<i64> {
+    let entries = entries
+        .iter()
+        .map(|&(row, col, val)| (row, col, (val % modulo + modulo) % modulo))
+        .collect::<Vec<_>>();
+    let mut rng = XorShift::new();
+    let u = (0..n)
+        .map(|_| rng.next(modulo as u64) as i64)
+        .collect::<Vec<_>>();
+    let mut v = (0..n)
+        .map(|_| rng.next(modulo as u64) as i64)
+        .collect::<Vec<_>>();
+    let mut vals = Vec::with_capacity(2 * n);
+
+    for _ in 0..2 * n {
+        vals.push(
+            u.iter()
+                .zip(v.iter())
+                .fold(0, |acc, (&a, &b)| (acc + a * b) % modulo),
+        );
+        v = sparse_multiply(n, &entries, &v, modulo);
+    }
+
+    let rec = berlekamp_massey(&vals, modulo);
+    let d = rec.len();
+    let mut ret = vec![0; d + 1];
+
+    ret[d] = 1;
+
+    for j in 0..d {
+        ret[d - j - 1] = (modulo - rec[j]) % modulo;
+    }
+
+    ret
+}
+
+// Determinant of the sparse matrix with high probability, modulo must be a large prime
+fn sparse_determinant(n: usize, entries: &[(usize, usize, i64)], modulo: i64) -> i64 {
+    // A random diagonal scaling makes the minimal polynomial equal to the characteristic one
+    let mut rng = XorShift::new();
+    let diag = (0..n)
+        .map(|_| rng.next(modulo as u64 - 1) as i64 + 1)
+        .collect::<Vec<_>>();
+    let scaled = entries
+        .iter()
+        .map(|&(row, col, val)| {
+            (
+                row,
+                col,
+                (val % modulo + modulo) % modulo * diag[col] % modulo,
+            )
+        })
+        .collect::<Vec<_>>();
+    let poly = sparse_minimal_polynomial(n, &scaled, modulo);
+
+    if poly.len() != n + 1 {
+        return 0;
+    }
+
+    let mut det = if n.is_multiple_of(2) {
+        poly[0]
+    } else {
+        (modulo - poly[0]) % modulo
+    };
+
+    for &d in diag.iter() {
+        det = det * pow(d, modulo - 2, modulo) % modulo;
+    }
+
+    det
+}
@@ -0,0 +1,517 @@
+use std::collections::BinaryHeap;
+use std::fmt;
+
+// Unsigned dimensions, the optimal cost must fit in the same type
+// Intermediate values may wrap around, every compared or divided value is exact
+trait ChainNum: Copy + Ord + fmt::Debug {
+    const ZERO: Self;
+
+    fn add(self, other: Self) -> Self;
+    fn sub(self, other: Self) -> Self;
+    fn mul(self, other: Self) -> Self;
+    fn div(self, other: Self) -> Self;
+}
+
+macro_rules! impl_chain_num {
+    ($($t:ty),*) => {
+        $(
+            impl ChainNum for $t {
+                const ZERO: Self = 0;
+
+                fn add(self, other: Self) -> Self {
+                    self.wrapping_add(other)
+                }
+
+                fn sub(self, other: Self) -> Self {
+                    self.wrapping_sub(other)
+                }
+
+                fn mul(self, other: Self) -> Self {
+                    self.wrapping_mul(other)
+                }
+
+                fn div(self, other: Self) -> Self {
+                    self / other
+                }
+            }
+        )*
+    };
+}
+
+impl_chain_num!(u64, u128);
+
+// Node of a parenthesization, multiplying matrices lo..hi
+// Leaves hold a single matrix, inner nodes the products lo..mid and mid..hi
+#[derive(Clone, Debug)]
+struct ChainNode {
+    lo: usize,
+    hi: usize,
+    children: Option<(usize, usize)>,
+}
+
+// nodes[0] is the root and every child comes after its parent
+#[derive(Clone, Debug)]
+struct ChainTree {
+    nodes: Vec<ChainNode>,
+}
+
+impl ChainTree {
+    // split(lo, hi) gives the mid of the product lo..hi
+    fn from_splits(m: usize, split: impl Fn(usize, usize) -> usize) -> Self {
+        let mut nodes = vec![ChainNode {
+            lo: 0,
+            hi: m,
+            children: None,
+        }];
+        let mut idx = 0;
+
+        while idx < nodes.len() {
+            let (lo, hi) = (nodes[idx].lo, nodes[idx].hi);
+
+            if hi - lo >= 2 {
+                let mid = split(lo, hi);
+                let left = nodes.len();
+
+                nodes.push(ChainNode {
+                    lo,
+                    hi: mid,
+                    children: None,
+                });
+                nodes.push(ChainNode {
+                    lo: mid,
+                    hi,
+                    children: None,
+                });
+                nodes[idx].children = Some((left, left + 1));
+            }
+
+            idx += 1;
+        }
+
+        Self { nodes }
+    }
+
+    // Number of scalar multiplications, matrix i is dims[i] x dims[i + 1]
+    fn cost<T: ChainNum>(&self, dims: &[T]) -> T {
+        let mut costs = vec![T::ZERO; self.nodes.len()];
+
+        for (i, node) in self.nodes.iter().enumerate().rev() {
+            if let Some((left, right)) = node.children {
+                let mid = self.nodes[left].hi;
+                costs[i] = costs[left]
+                    .add(costs[right])
+                    .add(dims[node.lo].mul(dims[mid]).mul(dims[node.hi]));
+            }
+        }
+
+        costs[0]
+    }
+}
+
+// Matrices written as their indices, such as ((0 1) 2)
+impl fmt::Display for ChainTree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.nodes[0].hi == 0 {
+            return Ok(());
+        }
+
+        // Some(node) opens a node, None closes the innermost one
+        let mut stack = vec![Some(0)];
+
+        while let Some(top) = stack.pop() {
+            match top.map(|idx| (idx, &self.nodes[idx])) {
+                None => write!(f, ")")?,
+                Some((_, node)) if node.children.is_none() => write!(f, "{}", node.lo)?,
+                Some((_, node)) => {
+                    let (left, right) = node.children.unwrap();
+
+                    write!(f, "(")?;
+                    stack.extend([None, Some(right), Some(usize::MAX), Some(left)]);
+                }
+            }
+
+            // The separator between two siblings
+            if stack.last() == Some(&Some(usize::MAX)) {
+                stack.pop();
+                write!(f, " ")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Textbook O(n^3) interval DP, used as a reference for small inputs
+fn matrix_chain_cubic<T: ChainNum>(dims: &[T]) -> (T, ChainTree) {
+    let m = dims.len().saturating_sub(1);
+    let mut dp = vec![vec![T::ZERO; m + 1]; m + 1];
+    let mut split = vec![vec![0; m + 1]; m + 1];
+
+    for len in 2..=m {
+        for lo in 0..=m - len {
+            let hi = lo + len;
+
+            for mid in lo + 1..hi {
+                let val = dp[lo][mid]
+                    .add(dp[mid][hi])
+                    .add(dims[lo].mul(dims[mid]).mul(dims[hi]));
+
+                if mid == lo + 1 || val < dp[lo][hi] {
+                    dp[lo][hi] = val;
+                    split[lo][hi] = mid;
+                }
+            }
+        }
+    }
+
+    (dp[0][m], ChainTree::from_splits(m, |lo, hi| split[lo][hi]))
+}
+
+#[derive(Clone)]
+struct HArc<T: ChainNum> {
+    u: usize,
+    v: usize,
+    low: usize,
+    bm: T,
+    bj: T,
+    base: T,
+    uv: T,
+}
+
+impl<T: ChainNum> HArc<T> {
+    fn new(u: usize, v: usize, w: &[T], cp: &[T]) -> Self {
+        let uv = w[u].mul(w[v]);
+        let base = cp[v].sub(cp[u]).sub(uv);
+
+        Self {
+            u,
+            v,
+            low: if w[u] <= w[v] { u } else { v },
+            bm: base,
+            bj: T::ZERO,
+            base,
+            uv,
+        }
+    }
+
+    fn is_contain(&self, arc: &Self) -> bool {
+        self.u <= arc.u && self.v >= arc.v
+    }
+
+    fn get_s(&self) -> T {
+        self.bj.div(self.bm)
+    }
+}
+
+// Reference: T. C. Hu and M. T. Shing, Computation of Matrix Chain Products
+// Optimal triangulation of the polygon of dimensions in O(n log n)
+struct HuShing<T: ChainNum> {
+    w: Vec<T>,
+    cp: Vec<T>,
+    n: usize,
+    h: Vec<HArc<T>>,
+    child: Vec<Vec<usize>>,
+    connect: Vec<Vec<usize>>,
+    ceiling: Vec<Vec<usize>>,
+    // Arcs bounding the fan of each arc from below when it was costed
+    floor: Vec<Vec<usize>>,
+}
+
+impl<T: ChainNum> HuShing<T> {
+    // w is the polygon already rotated so that w[0] is the minimum, with n >= 3
+    fn new(mut w: Vec<T>) -> Self {
+        let n = w.len();
+        w.push(w[0]);
+
+        let mut cp = vec![T::ZERO; n + 1];
+
+        for i in 1..=n {
+            cp[i] = cp[i - 1].add(w[i].mul(w[i - 1]));
+        }
+
+        Self {
+            w,
+            cp,
+            n,
+            h: Vec::with_capacity(n),
+            child: vec![Vec::new(); n],
+            connect: vec![Vec::new(); n + 1],
+            ceiling: vec![Vec::new(); n],
+            floor: vec![Vec::new(); n],
+        }
+    }
+
+    fn solve(&mut self) -> T {
+        self.build_tree();
+
+        for vertex in self.post_order() {
+            self.process_arc(vertex);
+        }
+
+        self.get_ans()
+    }
+
+    fn build_tree(&mut self) {
+        let arcs = self.process_one_sweep();
+        let mut stack: Vec<usize> = Vec::new();
+
+        self.h.push(HArc::new(0, self.n, &self.w, &self.cp));
+
+        for (u, v) in arcs {
+            if u == 0 || v == 0 {
+                continue;
+            }
+
+            self.h.push(HArc::new(u, v, &self.w, &self.cp));
+
+            let last = self.h.len() - 1;
+
+            while let Some(&top) = stack.last() {
+                if !self.h[last].is_contain(&self.h[top]) {
+                    break;
+                }
+
+                self.child[last].push(top);
+                stack.pop();
+            }
+
+            stack.push(last);
+        }
+
+        while let Some(top) = stack.pop() {
+            self.child[0].push(top);
+        }
+    }
+
+    fn process_one_sweep(&self) -> Vec<(usize, usize)> {
+        let mut stack: Vec<usize> = Vec::new();
+        let mut arcs = Vec::new();
+
+        for i in 0..self.n {
+            while stack.len() >= 2 && self.w[*stack.last().unwrap()] > self.w[i] {
+                arcs.push((stack[stack.len() - 2], i));
+                stack.pop();
+            }
+
+            stack.push(i);
+        }
+
+        while stack.len() >= 3 {
+            arcs.push((0, stack[stack.len() - 2]));
+            stack.pop();
+        }
+
+        arcs
+    }
+
+    // Children in the order a recursive DFS would finish them, without deep recursion
+    fn post_order(&self) -> Vec<usize> {
+        let mut ret = Vec::with_capacity(self.h.len());
+        let mut stack = vec![(0, 0)];
+
+        while let Some((vertex, idx)) = stack.pop() {
+            if idx < self.child[vertex].len() {
+                stack.push((vertex, idx + 1));
+                stack.push((self.child[vertex][idx], 0));
+            } else {
+                ret.push(vertex);
+            }
+        }
+
+        ret
+    }
+
+    fn process_arc(&mut self, vertex: usize) {
+        let mut heap: BinaryHeap<(T, usize)> = BinaryHeap::new();
+
+        for i in 0..self.child[vertex].len() {
+            let next = self.child[vertex][i];
+
+            self.h[vertex].bm = self.h[vertex].bm.sub(self.h[next].base);
+            heap.push((self.h[next].get_s(), next));
+        }
+
+        while let Some(&(s, idx)) = heap.peek() {
+            if s < self.w[self.h[vertex].low] {
+                break;
+            }
+
+            heap.pop();
+            self.h[vertex].bm = self.h[vertex].bm.add(self.h[idx].bm);
+            self.remove_arc(idx);
+
+            for i in 0..self.ceiling[idx].len() {
+                let next = self.ceiling[idx][i];
+                heap.push((self.h[next].get_s(), next));
+            }
+        }
+
+        self.h[vertex].bj = self.get_fan_cost(vertex);
+        self.floor[vertex] = heap.iter().map(|&(_, idx)| idx).collect();
+
+        while let Some((s, idx)) = heap.pop() {
+            if self.h[vertex].get_s() <= s {
+                self.h[vertex].bm = self.h[vertex].bm.add(self.h[idx].bm);
+                self.remove_arc(idx);
+                self.h[vertex].bj = self.h[vertex].bj.add(self.h[idx].bj);
+
+                for i in 0..self.ceiling[idx].len() {
+                    let next = self.ceiling[idx][i];
+                    heap.push((self.h[next].get_s(), next));
+                }
+            } else {
+                self.ceiling[vertex].push(idx);
+            }
+        }
+
+        self.add_arc(vertex);
+    }
+
+    fn add_arc(&mut self, vertex: usize) {
+        self.connect[self.h[vertex].u].push(vertex);
+        self.connect[self.h[vertex].v].push(vertex);
+    }
+
+    fn remove_arc(&mut self, vertex: usize) {
+        self.connect[self.h[vertex].u].pop();
+        self.connect[self.h[vertex].v].pop();
+    }
+
+    fn get_fan_cost(&self, vertex: usize) -> T {
+        let arc = &self.h[vertex];
+
+        self.w[arc.low].mul(arc.bm.add(arc.uv).sub(self.exclude_cp(vertex)))
+    }
+
+    fn exclude_cp(&self, vertex: usize) -> T {
+        if vertex == 0 {
+            return self.w[0]
+                .mul(self.w[1])
+                .add(self.w[0].mul(self.w[self.n - 1]));
+        }
+
+        let arc = &self.h[vertex];
+        let (end, neighbor) = if arc.low == arc.u {
+            (arc.u, arc.u + 1)
+        } else {
+            (arc.v, arc.v - 1)
+        };
+
+        match self.connect[end].last() {
+            Some(&top) if arc.is_contain(&self.h[top]) => self.h[top].uv,
+            _ => self.w[end].mul(self.w[neighbor]),
+        }
+    }
+
+    fn get_ans(&self) -> T {
+        let mut ret = T::ZERO;
+        let mut stack = vec![0];
+
+        while let Some(vertex) = stack.pop() {
+            ret = ret.add(self.h[vertex].bj);
+            stack.extend(self.ceiling[vertex].iter().copied());
+        }
+
+        ret
+    }
+
+    // Triangles of the optimal partition, every kept arc bounds a region below it
+    // which is a fan from its lighter endpoint
+    // Arcs merged after an arc was costed keep their own fan, so the walk follows floor
+    fn triangles(&self) -> Vec<[usize; 3]> {
+        let mut ret = Vec::with_capacity(self.n - 2);
+        let mut stack = vec![0];
+        let mut jump = (1..=self.n + 1).collect::<Vec<_>>();
+
+        while let Some(vertex) = stack.pop() {
+            let arc = &self.h[vertex];
+
+            for &next in self.floor[vertex].iter() {
+                jump[self.h[next].u] = self.h[next].v;
+                stack.push(next);
+            }
+
+            let mut region = vec![arc.u];
+            let mut p = arc.u;
+
+            while p < arc.v {
+                p = jump[p];
+                region.push(p);
+            }
+
+            for &next in self.floor[vertex].iter() {
+                jump[self.h[next].u] = self.h[next].u + 1;
+            }
+
+            // Vertex n is vertex 0 again, so the root region closes on itself
+            let (apex, edges) = if vertex == 0 {
+                (0, &region[1..region.len() - 1])
+            } else if arc.low == arc.u {
+                (arc.u, &region[1..])
+            } else {
+                (arc.v, &region[..region.len() - 1])
+            };
+
+            for pair in edges.windows(2) {
+                ret.push([apex, pair[0], pair[1]]);
+            }
+        }
+
+        ret
+    }
+}
+
+// Optimal order to multiply matrices where matrix i is dims[i] x dims[i + 1] in O(n log n)
+// Returns the number of scalar multiplications and the parenthesization achieving it
+// Every dimension must be positive, Hu-Shing divides by them
+fn matrix_chain_order<T: ChainNum>(dims: &[T]) -> (T, ChainTree) {
+    let n = dims.len();
+
+    assert!(
+        dims.iter().all(|&d| d != T::ZERO),
+        "Dimensions must be positive"
+    );
+
+    if n <= 3 {
+        return matrix_chain_cubic(dims);
+    }
+
+    // The polygon of dimensions is rotated to start at its smallest vertex
+    let offset = (0..n).min_by_key(|&i| dims[i]).unwrap();
+    let mut w = dims.to_vec();
+    w.rotate_left(offset);
+
+    let mut hu_shing = HuShing::new(w);
+    let cost = hu_shing.solve();
+
+    // Triangle (a, b, c) with a < b < c splits the product a..c at b
+    let mut split = hu_shing
+        .triangles()
+        .into_iter()
+        .map(|triangle| {
+            let mut t = triangle.map(|x| (x + offset) % n);
+            t.sort_unstable();
+            (t[0], t[2], t[1])
+        })
+        .collect::<Vec<_>>();
+    split.sort_unstable();
+
+    let tree = ChainTree::from_splits(n - 1, |lo, hi| {
+        let idx = split.partition_point(|&(a, c, _)| (a, c) < (lo, hi));
+        split[idx].2
+    });
+
+    debug_assert!(
+        tree.cost(dims) == cost,
+        "Parenthesization disagrees with the cost"
+    );
+
+    if cfg!(debug_assertions) && n <= 200 {
+        assert!(
+            matrix_chain_cubic(dims).0 == cost,
+            "Hu-Shing disagrees with the O(n^3) DP"
+        );
+    }
+
+    (cost, tree)
+}
@@ -0,0 +1,404 @@
+// Every range is half-open, a query (l, r) asks about the elements l..r
+// add_* grows the window by the element idx, remove_* shrinks it by the element idx
+trait MoState {
+    type Answer;
+
+    fn add_left(&mut self, idx: usize);
+    fn add_right(&mut self, idx: usize);
+    fn remove_left(&mut self, idx: usize);
+    fn remove_right(&mut self, idx: usize);
+    fn answer(&self) -> Self::Answer;
+}
+
+// Point updates for Mo with time, update t moves the array from time t to t + 1
+// The window l..r is passed so that an update only touches the state inside it
+trait MoUpdate: MoState {
+    fn apply(&mut self, t: usize, l: usize, r: usize);
+    fn revert(&mut self, t: usize, l: usize, r: usize);
+}
+
+// States which can only grow, such as a maximum or a union-find
+// rollback restores the state saved by the last snapshot, reset empties it
+trait MoRollbackState {
+    type Answer;
+
+    fn add_left(&mut self, idx: usize);
+    fn add_right(&mut self, idx: usize);
+    fn snapshot(&mut self);
+    fn rollback(&mut self);
+    fn reset(&mut self);
+    fn answer(&self) -> Self::Answer;
+}
+
+// Paths of a tree, toggle adds a vertex that is out and removes a vertex that is in
+trait MoTreeState {
+    type Answer;
+
+    fn toggle(&mut self, vertex: usize);
+    fn answer(&self) -> Self::Answer;
+}
+
+// Reference: https://en.wikipedia.org/wiki/Hilbert_curve
+// Position of (x, y) on the Hilbert curve filling the square of side 2^pow
+fn hilbert_order(mut x: u64, mut y: u64, pow: u32) -> u64 {
+    let side = 1u64 << pow;
+    let mut ret = 0;
+    let mut s = side >> 1;
+
+    while s > 0 {
+        let rx = (x & s > 0) as u64;
+        let ry = (y & s > 0) as u64;
+        ret += s * s * ((3 * rx) ^ ry);
+
+        if ry == 0 {
+            if rx == 1 {
+                x = side - 1 - x;
+                y = side - 1 - y;
+            }
+
+            std::mem::swap(&mut x, &mut y);
+        }
+
+        s >>= 1;
+    }
+
+    ret
+}
+
+// Queries sorted along the Hilbert curve, about n * sqrt(q) moves in total
+fn hilbert_sort(n: usize, queries: &[(usize, usize)]) -> Vec<usize> {
+    let mut pow = 0;
+
+    while (1usize << pow) <= n {
+        pow += 1;
+    }
+
+    let keys = queries
+        .iter()
+        .map(|&(l, r)| hilbert_order(l as u64, r as u64, pow))
+        .collect::<Vec<_>>();
+    let mut ret = (0..queries.len()).collect::<Vec<_>>();
+    ret.sort_unstable_by_key(|&i| keys[i]);
+
+    ret
+}
+
+// Classic order by the block of l, r alternates direction between blocks
+fn block_sort(n: usize, queries: &[(usize, usize)]) -> Vec<usize> {
+    let block = (n as f64 / (queries.len().max(1) as f64).sqrt())
+        .ceil()
+        .max(1.0) as usize;
+    let mut ret = (0..queries.len()).collect::<Vec<_>>();
+
+    ret.sort_unstable_by_key(|&i| {
+        let (l, r) = queries[i];
+        let b = l / block;
+
+        (b, if b.is_multiple_of(2) { r } else { n - r })
+    });
+
+    ret
+}
+
+// Visits the queries in order and collects answer(state, query index) by query index
+fn process_mo<S: MoState, A>(
+    state: &mut S,
+    queries: &[(usize, usize)],
+    order: &[usize],
+    mut answer: impl FnMut(&mut S, usize) -> A,
+) -> Vec<A> {
+    let mut ret = queries.iter().map(|_| None).collect::<Vec<_>>();
+    let (mut l, mut r) = (0, 0);
+
+    // Growing first keeps l <= r at all times
+    for &i in order.iter() {
+        let (ql, qr) = queries[i];
+
+        while l > ql {
+            l -= 1;
+            state.add_left(l);
+        }
+
+        while r < qr {
+            state.add_right(r);
+            r += 1;
+        }
+
+        while l < ql {
+            state.remove_left(l);
+            l += 1;
+        }
+
+        while r > qr {
+            r -= 1;
+            state.remove_right(r);
+        }
+
+        ret[i] = Some(answer(state, i));
+    }
+
+    ret.into_iter().map(|x| x.unwrap()).collect()
+}
+
+// Answers every query on an array of length n in O(n sqrt(q)) moves
+// The Hilbert order is usually faster, the block order moves less on few queries
+fn mo<S: MoState>(
+    state: &mut S,
+    n: usize,
+    queries: &[(usize, usize)],
+    hilbert: bool,
+) -> Vec<S::Answer> {
+    let order = if hilbert {
+        hilbert_sort(n, queries)
+    } else {
+        block_sort(n, queries)
+    };
+
+    process_mo(state, queries, &order, |state, _| state.answer())
+}
+
+// Queries (l, r, t) ask about l..r after the first t updates
+// O(n^(5/3)) moves for n, q and the number of updates of the same order
+fn mo_with_updates<S: MoUpdate>(
+    state: &mut S,
+    n: usize,
+    queries: &[(usize, usize, usize)],
+) -> Vec<S::Answer> {
+    let block = ((n as f64).powf(2.0 / 3.0).ceil() as usize).max(1);
+    let mut order = (0..queries.len()).collect::<Vec<_>>();
+
+    order.sort_unstable_by_key(|&i| {
+        let (l, r, t) = queries[i];
+        let (bl, br) = (l / block, r / block);
+
+        (
+            bl,
+            if bl.is_multiple_of(2) { br } else { n - br },
+            if br.is_multiple_of(2) {
+                t
+            } else {
+                usize::MAX - t
+            },
+        )
+    });
+
+    let ranges = queries.iter().map(|&(l, r, _)| (l, r)).collect::<Vec<_>>();
+    let mut time = 0;
+
+    process_mo(state, &ranges, &order, |state, i| {
+        let (l, r, t) = queries[i];
+
+        while time < t {
+            state.apply(time, l, r);
+            time += 1;
+        }
+
+        while time > t {
+            time -= 1;
+            state.revert(time, l, r);
+        }
+
+        state.answer()
+    })
+}
+
+// Mo without removals in O(n sqrt(q)) additions
+// Queries are grouped by the block of l, the right part grows across a block
+// and the left part is rolled back after every query
+fn mo_rollback<S: MoRollbackState>(
+    state: &mut S,
+    n: usize,
+    queries: &[(usize, usize)],
+) -> Vec<S::Answer> {
+    let block = (n as f64 / (queries.len().max(1) as f64).sqrt())
+        .ceil()
+        .max(1.0) as usize;
+    let mut order = (0..queries.len()).collect::<Vec<_>>();
+    order.sort_unstable_by_key(|&i| (queries[i].0 / block, queries[i].1));
+
+    let mut ret = queries.iter().map(|_| None).collect::<Vec<_>>();
+    let mut idx = 0;
+
+    while idx < order.len() {
+        let b = queries[order[idx]].0 / block;
+        let end = ((b + 1) * block).min(n);
+        let mut r = end;
+
+        state.reset();
+
+        while idx < order.len() && queries[order[idx]].0 / block == b {
+            let i = order[idx];
+            let (ql, qr) = queries[i];
+
+            if qr <= end {
+                // Short queries lie inside the block and are built from scratch
+                state.snapshot();
+
+                for j in ql..qr {
+                    state.add_right(j);
+                }
+            } else {
+                while r < qr {
+                    state.add_right(r);
+                    r += 1;
+                }
+
+                state.snapshot();
+
+                for j in (ql..end).rev() {
+                    state.add_left(j);
+                }
+            }
+
+            ret[i] = Some(state.answer());
+            state.rollback();
+            idx += 1;
+        }
+    }
+
+    ret.into_iter().map(|x| x.unwrap()).collect()
+}
+
+// Window over the Euler tour, a vertex is on the path when it appears once in the window
+struct TourWindow<'a, S: MoTreeState> {
+    tour: Vec<usize>,
+    state: &'a mut S,
+}
+
+impl<'a, S: MoTreeState> MoState for TourWindow<'a, S> {
+    type Answer = S::Answer;
+
+    fn add_left(&mut self, idx: usize) {
+        self.state.toggle(self.tour[idx]);
+    }
+
+    fn add_right(&mut self, idx: usize) {
+        self.state.toggle(self.tour[idx]);
+    }
+
+    fn remove_left(&mut self, idx: usize) {
+        self.state.toggle(self.tour[idx]);
+    }
+
+    fn remove_right(&mut self, idx: usize) {
+        self.state.toggle(self.tour[idx]);
+    }
+
+    fn answer(&self) -> Self::Answer {
+        self.state.answer()
+    }
+}
+
+// Queries (u, v) ask about the vertices of the path from u to v in a tree rooted at 0
+fn mo_tree<S: MoTreeState>(
+    state: &mut S,
+    adj: &[Vec<usize>],
+    queries: &[(usize, usize)],
+) -> Vec<S::Answer> {
+    let n = adj.len();
+
+    if n == 0 {
+        return Vec::new();
+    }
+
+    // Iterative DFS, every vertex enters the tour at tin and leaves it at tout
+    let mut tour = Vec::with_capacity(2 * n);
+    let mut tin = vec![0; n];
+    let mut tout = vec![0; n];
+    let mut depth = vec![0; n];
+    let mut log = 1;
+
+    while (1 << log) < n {
+        log += 1;
+    }
+
+    let mut up = vec![vec![0; n]; log];
+    let mut stack = vec![(0, usize::MAX, 0)];
+
+    tour.push(0);
+
+    while let Some((vertex, parent, idx)) = stack.pop() {
+        if idx == adj[vertex].len() {
+            tout[vertex] = tour.len();
+            tour.push(vertex);
+            continue;
+        }
+
+        stack.push((vertex, parent, idx + 1));
+
+        let next = adj[vertex][idx];
+
+        if next != parent {
+            up[0][next] = vertex;
+            depth[next] = depth[vertex] + 1;
+            tin[next] = tour.len();
+            tour.push(next);
+            stack.push((next, vertex, 0));
+        }
+    }
+
+    for k in 1..log {
+        for v in 0..n {
+            up[k][v] = up[k - 1][up[k - 1][v]];
+        }
+    }
+
+    let lca = |mut u: usize, mut v: usize| {
+        if depth[u] < depth[v] {
+            std::mem::swap(&mut u, &mut v);
+        }
+
+        for k in (0..log).rev() {
+            if depth[u] >= depth[v] + (1 << k) {
+                u = up[k][u];
+            }
+        }
+
+        if u == v {
+            return u;
+        }
+
+        for k in (0..log).rev() {
+            if up[k][u] != up[k][v] {
+                u = up[k][u];
+                v = up[k][v];
+            }
+        }
+
+        up[0][u]
+    };
+
+    // The lca is missing from the window unless it is an endpoint
+    let mut ranges = Vec::with_capacity(queries.len());
+    let mut extra = Vec::with_capacity(queries.len());
+
+    for &(u, v) in queries.iter() {
+        let (u, v) = if tin[u] <= tin[v] { (u, v) } else { (v, u) };
+        let w = lca(u, v);
+
+        if w == u {
+            ranges.push((tin[u], tin[v] + 1));
+            extra.push(None);
+        } else {
+            ranges.push((tout[u], tin[v] + 1));
+            extra.push(Some(w));
+        }
+    }
+
+    let order = hilbert_sort(tour.len(), &ranges);
+    let mut window = TourWindow { tour, state };
+
+    process_mo(&mut window, &ranges, &order, |window, i| {
+        if let Some(w) = extra[i] {
+            window.state.toggle(w);
+        }
+
+        let ret = window.state.answer();
+
+        if let Some(w) = extra[i] {
+            window.state.toggle(w);
+        }
+
+        ret
+    })
+}
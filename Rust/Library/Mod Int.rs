@@ -0,0 +1,404 @@
+use std::cell::Cell;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+#[derive(Debug)]
+struct ParseModIntError;
+
+// Inverse of a modulo m by extended Euclid, m does not need to be prime
+fn inv_mod(a: u64, m: u64) -> Option<u64> {
+    let (mut old_r, mut r) = (a as i128, m as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+    }
+
+    if old_r != 1 {
+        return None;
+    }
+
+    Some(old_s.rem_euclid(m as i128) as u64)
+}
+
+// Shared arithmetic for ModInt and DynamicModInt, which provide raw, val, modulus and mul_raw
+macro_rules! impl_mod_int_ops {
+    ($t:ty $(, $generic:tt)?) => {
+        impl<$(const $generic: u32)?> $t {
+            fn new(val: i64) -> Self {
+                Self::raw(val.rem_euclid(Self::modulus() as i64) as u32)
+            }
+
+            fn pow(self, mut exp: u64) -> Self {
+                let mut ret = Self::raw(1 % Self::modulus());
+                let mut piv = self;
+
+                while exp > 0 {
+                    if exp & 1 != 0 {
+                        ret *= piv;
+                    }
+
+                    piv *= piv;
+                    exp >>= 1;
+                }
+
+                ret
+            }
+
+            // Panics if the value is not coprime to the modulus
+            fn inv(self) -> Self {
+                let ret = inv_mod(self.val() as u64, Self::modulus() as u64);
+                Self::raw(ret.expect("Not invertible") as u32)
+            }
+        }
+
+        impl<$(const $generic: u32)?> Add for $t {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                let ret = self.val() + other.val();
+                Self::raw(if ret >= Self::modulus() { ret - Self::modulus() } else { ret })
+            }
+        }
+
+        impl<$(const $generic: u32)?> Sub for $t {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                Self::raw(if self.val() >= other.val() {
+                    self.val() - other.val()
+                } else {
+                    self.val() + Self::modulus() - other.val()
+                })
+            }
+        }
+
+        impl<$(const $generic: u32)?> Mul for $t {
+            type Output = Self;
+
+            fn mul(self, other: Self) -> Self {
+                Self::raw(Self::mul_raw(self.val(), other.val()))
+            }
+        }
+
+        impl<$(const $generic: u32)?> Div for $t {
+            type Output = Self;
+
+            #[allow(clippy::suspicious_arithmetic_impl)]
+            fn div(self, other: Self) -> Self {
+                self * other.inv()
+            }
+        }
+
+        impl<$(const $generic: u32)?> Neg for $t {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self::raw(0) - self
+            }
+        }
+
+        impl<$(const $generic: u32)?> AddAssign for $t {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl<$(const $generic: u32)?> SubAssign for $t {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl<$(const $generic: u32)?> MulAssign for $t {
+            fn mul_assign(&mut self, other: Self) {
+                *self = *self * other;
+            }
+        }
+
+        impl<$(const $generic: u32)?> DivAssign for $t {
+            fn div_assign(&mut self, other: Self) {
+                *self = *self / other;
+            }
+        }
+
+        impl<$(const $generic: u32)?> Sum for $t {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::raw(0), |acc, x| acc + x)
+            }
+        }
+
+        impl<$(const $generic: u32)?> Product for $t {
+            fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::raw(1 % Self::modulus()), |acc, x| acc * x)
+            }
+        }
+
+        impl<$(const $generic: u32)?> From<i64> for $t {
+            fn from(val: i64) -> Self {
+                Self::new(val)
+            }
+        }
+
+        impl<$(const $generic: u32)?> fmt::Display for $t {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.val())
+            }
+        }
+
+        impl<$(const $generic: u32)?> fmt::Debug for $t {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.val())
+            }
+        }
+
+        // Accepts decimal strings of any length, reducing digit by digit
+        impl<$(const $generic: u32)?> FromStr for $t {
+            type Err = ParseModIntError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let (negative, digits) = match s.strip_prefix('-') {
+                    Some(rest) => (true, rest),
+                    None => (false, s.strip_prefix('+').unwrap_or(s)),
+                };
+
+                if digits.is_empty() {
+                    return Err(ParseModIntError);
+                }
+
+                let ten = Self::new(10);
+                let mut ret = Self::raw(0);
+
+                for c in digits.bytes() {
+                    if !c.is_ascii_digit() {
+                        return Err(ParseModIntError);
+                    }
+
+                    ret = ret * ten + Self::new((c - b'0') as i64);
+                }
+
+                Ok(if negative { -ret } else { ret })
+            }
+        }
+    };
+}
+
+// Modulus fixed at compile time, M must be below 2^31
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+struct ModInt<const M: u32>(u32);
+
+type ModInt998244353 = ModInt<998_244_353>;
+type ModInt1000000007 = ModInt<1_000_000_007>;
+
+impl<const M: u32> ModInt<M> {
+    fn raw(val: u32) -> Self {
+        Self(val)
+    }
+
+    fn val(self) -> u32 {
+        self.0
+    }
+
+    fn modulus() -> u32 {
+        M
+    }
+
+    fn mul_raw(a: u32, b: u32) -> u32 {
+        (a as u64 * b as u64 % M as u64) as u32
+    }
+}
+
+impl_mod_int_ops!(ModInt<M>, M);
+
+// Reference: https://github.com/atcoder/ac-library/blob/master/atcoder/internal_math.hpp
+#[derive(Clone, Copy)]
+struct Barrett {
+    m: u32,
+    im: u64,
+}
+
+impl Barrett {
+    fn new(m: u32) -> Self {
+        Self {
+            m,
+            im: (u64::MAX / m as u64).wrapping_add(1),
+        }
+    }
+
+    // a * b mod m for a, b < m
+    fn mul(&self, a: u32, b: u32) -> u32 {
+        let z = a as u64 * b as u64;
+        let x = ((z as u128 * self.im as u128) >> 64) as u64;
+        let y = x.wrapping_mul(self.m as u64);
+
+        (z.wrapping_sub(y) as u32).wrapping_add(if z < y { self.m } else { 0 })
+    }
+}
+
+thread_local! {
+    static BARRETT: Cell<Barrett> = Cell::new(Barrett::new(998_244_353));
+}
+
+// Modulus chosen at runtime with set_modulus, shared by every DynamicModInt on the thread
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+struct DynamicModInt(u32);
+
+impl DynamicModInt {
+    // Values created under a previous modulus must not be mixed with new ones
+    fn set_modulus(m: u32) {
+        assert!((1..1 << 31).contains(&m));
+        BARRETT.with(|b| b.set(Barrett::new(m)));
+    }
+
+    fn raw(val: u32) -> Self {
+        Self(val)
+    }
+
+    fn val(self) -> u32 {
+        self.0
+    }
+
+    fn modulus() -> u32 {
+        BARRETT.with(|b| b.get().m)
+    }
+
+    fn mul_raw(a: u32, b: u32) -> u32 {
+        BARRETT.with(|barrett| barrett.get().mul(a, b))
+    }
+}
+
+impl_mod_int_ops!(DynamicModInt);
+
+// Montgomery form for an odd runtime modulus
+#[derive(Clone, Copy)]
+struct Montgomery {
+    n: u64,
+    n_inv: u64,
+    r2: u64,
+}
+
+impl Montgomery {
+    fn new(n: u64) -> Self {
+        assert!(n % 2 == 1);
+
+        // Newton iteration doubles the number of correct low bits every step
+        let mut n_inv = n;
+
+        for _ in 0..5 {
+            n_inv = n_inv.wrapping_mul(2u64.wrapping_sub(n.wrapping_mul(n_inv)));
+        }
+
+        let r = ((1u128 << 64) % n as u128) as u64;
+
+        Self {
+            n,
+            n_inv,
+            r2: (r as u128 * r as u128 % n as u128) as u64,
+        }
+    }
+
+    fn reduce(&self, t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(self.n_inv);
+        let mn = ((m as u128 * self.n as u128) >> 64) as u64;
+        let high = (t >> 64) as u64;
+
+        // high - mn lies in (-n, n), so the wrapped sum is exact
+        if high >= mn {
+            high - mn
+        } else {
+            high.wrapping_sub(mn).wrapping_add(self.n)
+        }
+    }
+
+    fn encode(&self, a: u64) -> u64 {
+        self.reduce((a % self.n) as u128 * self.r2 as u128)
+    }
+
+    fn decode(&self, a: u64) -> u64 {
+        self.reduce(a as u128)
+    }
+
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+
+    // Both the base and the result are in Montgomery form
+    fn pow(&self, mut base: u64, mut exp: u64) -> u64 {
+        let mut ret = self.encode(1);
+
+        while exp > 0 {
+            if exp & 1 != 0 {
+                ret = self.mul(ret, base);
+            }
+
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+
+        ret
+    }
+}
+
+// Factorial tables up to n for binomial coefficients modulo a prime M
+struct Binomial<const M: u32> {
+    fact: Vec<ModInt<M>>,
+    inv_fact: Vec<ModInt<M>>,
+}
+
+impl<const M: u32> Binomial<M> {
+    // n must be below M so that every factorial is invertible
+    fn new(n: usize) -> Self {
+        assert!(n < M as usize, "Factorial table past the modulus");
+
+        let mut fact = vec![ModInt::raw(1); n + 1];
+
+        for i in 1..=n {
+            fact[i] = fact[i - 1] * ModInt::new(i as i64);
+        }
+
+        let mut inv_fact = vec![ModInt::raw(1); n + 1];
+        inv_fact[n] = fact[n].inv();
+
+        for i in (1..=n).rev() {
+            inv_fact[i - 1] = inv_fact[i] * ModInt::new(i as i64);
+        }
+
+        Self { fact, inv_fact }
+    }
+
+    fn binom(&self, n: usize, k: usize) -> ModInt<M> {
+        if k > n {
+            return ModInt::raw(0);
+        }
+
+        self.fact[n] * self.inv_fact[k] * self.inv_fact[n - k]
+    }
+
+    fn perm(&self, n: usize, k: usize) -> ModInt<M> {
+        if k > n {
+            return ModInt::raw(0);
+        }
+
+        self.fact[n] * self.inv_fact[n - k]
+    }
+
+    // Lucas theorem for huge n, the table must have been built with n = M - 1
+    fn lucas(&self, mut n: u64, mut k: u64) -> ModInt<M> {
+        assert!(self.fact.len() == M as usize);
+
+        let m = M as u64;
+        let mut ret = ModInt::raw(1);
+
+        while n > 0 || k > 0 {
+            ret *= self.binom((n % m) as usize, (k % m) as usize);
+            n /= m;
+            k /= m;
+        }
+
+        ret
+    }
+}
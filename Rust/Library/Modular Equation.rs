@@ -0,0 +1,257 @@
+// Requires Integer Factorization.rs for gcd, pow_mod_u64 and smallest_primitive_root
+use std::collections::HashMap;
+
+fn mul_mod(a: u64, b: u64, modular: u64) -> u64 {
+    (a as u128 * b as u128 % modular as u128) as u64
+}
+
+// Safe for any modular up to 2^64 - 1, where a + b can exceed u64
+fn add_mod(a: u64, b: u64, modular: u64) -> u64 {
+    ((a as u128 + b as u128) % modular as u128) as u64
+}
+
+// (g, x, y) with a * x + b * y = g = gcd(a, b)
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+// Solutions of a * x = b (mod m) as x = x0 (mod step), m must be positive
+fn linear_congruence(a: i64, b: i64, m: i64) -> Option<(i64, i64)> {
+    let (a, b, m) = (a as i128, b as i128, m as i128);
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+
+    if b.rem_euclid(g) != 0 {
+        return None;
+    }
+
+    let step = m / g;
+    let x0 = (x.rem_euclid(step) * (b / g).rem_euclid(step)).rem_euclid(step);
+
+    Some((x0 as i64, step as i64))
+}
+
+// Merges x = r (mod m) for every pair, moduli must be positive but need not be coprime
+// Returns (r, lcm), None when the congruences conflict or the lcm overflows i128
+fn crt(pairs: &[(i64, i64)]) -> Option<(i128, i128)> {
+    let mut r0 = 0i128;
+    let mut m0 = 1i128;
+
+    for &(r, m) in pairs {
+        let (r1, m1) = ((r as i128).rem_euclid(m as i128), m as i128);
+        let (g, x, _) = extended_gcd(m0.rem_euclid(m1), m1);
+
+        if (r1 - r0).rem_euclid(g) != 0 {
+            return None;
+        }
+
+        // Both factors are below m1 / g, so the product fits in i128
+        let step = m1 / g;
+        let t = ((r1 - r0) / g).rem_euclid(step) * x.rem_euclid(step) % step;
+
+        let lcm = m0.checked_mul(step)?;
+        r0 = (r0 + m0 * t).rem_euclid(lcm);
+        m0 = lcm;
+    }
+
+    Some((r0, m0))
+}
+
+// Reference: https://cp-algorithms.com/algebra/discrete-log.html
+// Smallest x >= 0 with a^x = b (mod m) in O(sqrt(m)), a and m need not be coprime
+fn discrete_log(a: u64, b: u64, m: u64) -> Option<u64> {
+    if m == 1 {
+        return Some(0);
+    }
+
+    let mut m = m;
+    let a = a % m;
+    let mut b = b % m;
+    let mut k = 1 % m;
+    let mut add = 0;
+
+    // Strip common factors until a becomes invertible
+    loop {
+        let g = gcd(a, m);
+
+        if g == 1 {
+            break;
+        }
+
+        if b == k {
+            return Some(add);
+        }
+
+        if !b.is_multiple_of(g) {
+            return None;
+        }
+
+        b /= g;
+        m /= g;
+        add += 1;
+        k = mul_mod(k, a / g, m);
+    }
+
+    if b == k {
+        return Some(add);
+    }
+
+    let n = (m as f64).sqrt() as u64 + 1;
+    let mut baby = HashMap::new();
+    let mut cur = b;
+
+    for j in 0..n {
+        baby.insert(cur, j);
+        cur = mul_mod(cur, a, m);
+    }
+
+    let giant = pow_mod_u64(a, n, m);
+    let mut cur = k;
+
+    for i in 1..=n {
+        cur = mul_mod(cur, giant, m);
+
+        if let Some(&j) = baby.get(&cur) {
+            return Some(i * n - j + add);
+        }
+    }
+
+    None
+}
+
+// Reference: https://en.wikipedia.org/wiki/Cipolla%27s_algorithm
+// x with x^2 = a (mod p) for a prime p, the other root is p - x
+fn sqrt_mod(a: u64, p: u64) -> Option<u64> {
+    let a = a % p;
+
+    if a < 2 || p == 2 {
+        return Some(a);
+    }
+
+    if pow_mod_u64(a, (p - 1) / 2, p) != 1 {
+        return None;
+    }
+
+    // Find t such that t^2 - a is a non-residue, then work in F_p[sqrt(t^2 - a)]
+    let mut t = 1;
+
+    while pow_mod_u64(add_mod(mul_mod(t, t, p), p - a, p), (p - 1) / 2, p) != p - 1 {
+        t += 1;
+    }
+
+    let w = add_mod(mul_mod(t, t, p), p - a, p);
+    let mul = |(x1, y1): (u64, u64), (x2, y2): (u64, u64)| {
+        (
+            add_mod(mul_mod(x1, x2, p), mul_mod(mul_mod(y1, y2, p), w, p), p),
+            add_mod(mul_mod(x1, y2, p), mul_mod(y1, x2, p), p),
+        )
+    };
+
+    let mut ret = (1, 0);
+    let mut piv = (t, 1);
+    let mut exp = p / 2 + 1;
+
+    while exp > 0 {
+        if exp & 1 != 0 {
+            ret = mul(ret, piv);
+        }
+
+        piv = mul(piv, piv);
+        exp >>= 1;
+    }
+
+    Some(ret.0.min(p - ret.0))
+}
+
+// x with x^k = a (mod p) for a prime p through a discrete log base a primitive root
+// Runs in O(sqrt(p)), so p should stay around 10^12 or below
+fn kth_root_mod(a: u64, k: u64, p: u64) -> Option<u64> {
+    let a = a % p;
+
+    if k == 0 {
+        return if a == 1 % p { Some(1) } else { None };
+    }
+
+    if a == 0 {
+        return Some(0);
+    }
+
+    let g = smallest_primitive_root(p)?;
+    let t = discrete_log(g, a, p)?;
+    // Only k mod p - 1 matters, and reducing it first keeps the cast to i64 exact
+    let (y, _) = linear_congruence((k % (p - 1)) as i64, t as i64, p as i64 - 1)?;
+
+    Some(pow_mod_u64(g, y as u64, p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqrt_mod_near_u64_max() {
+        // The largest prime below 2^64, where sums of residues overflow u64
+        let p = 18_446_744_073_709_551_557;
+
+        for a in [4, 9, 2, 3, p - 1, p - 4, 123_456_789_012_345_678] {
+            match sqrt_mod(a, p) {
+                Some(x) => assert_eq!(mul_mod(x, x, p), a),
+                None => assert_eq!(pow_mod_u64(a, (p - 1) / 2, p), p - 1),
+            }
+        }
+
+        assert_eq!(sqrt_mod(4, p), Some(2));
+    }
+
+    #[test]
+    fn sqrt_mod_small_primes() {
+        for p in [2, 3, 5, 7, 13, 17, 1009] {
+            for a in 0..p {
+                let is_square = (0..p).any(|x| x * x % p == a);
+
+                match sqrt_mod(a, p) {
+                    Some(x) => assert_eq!(x * x % p, a),
+                    None => assert!(!is_square),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn crt_overflow_is_none() {
+        let big = (1i64 << 62) - 57;
+
+        assert_eq!(crt(&[(1, 6), (3, 10)]), Some((13, 30)));
+        assert_eq!(crt(&[(1, 6), (2, 10)]), None);
+        assert_eq!(crt(&[(1, big), (2, big - 2), (3, big - 4)]), None);
+    }
+
+    #[test]
+    fn kth_root_mod_huge_exponent() {
+        for p in [2, 13, 998_244_353] {
+            for k in [u64::MAX, 1 << 63, (p - 1) << 40] {
+                for a in 1..20 {
+                    if let Some(x) = kth_root_mod(a, k, p) {
+                        assert_eq!(pow_mod_u64(x, k, p), a % p);
+                    }
+                }
+            }
+
+            assert_eq!(kth_root_mod(1, (p - 1) << 40, p), Some(1));
+        }
+    }
+}
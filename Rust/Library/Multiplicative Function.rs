@@ -0,0 +1,279 @@
+// Primes, smallest prime factor, mobius and phi of every number up to n in O(n)
+struct LinearSieve {
+    primes: Vec<usize>,
+    spf: Vec<usize>,
+    mobius: Vec<i32>,
+    phi: Vec<usize>,
+}
+
+impl LinearSieve {
+    fn new(n: usize) -> Self {
+        let mut primes = Vec::new();
+        let mut spf = vec![0; n + 1];
+        let mut mobius = vec![0; n + 1];
+        let mut phi = vec![0; n + 1];
+
+        if n >= 1 {
+            mobius[1] = 1;
+            phi[1] = 1;
+        }
+
+        for i in 2..=n {
+            if spf[i] == 0 {
+                spf[i] = i;
+                mobius[i] = -1;
+                phi[i] = i - 1;
+                primes.push(i);
+            }
+
+            for &p in primes.iter() {
+                if p > spf[i] || i * p > n {
+                    break;
+                }
+
+                spf[i * p] = p;
+
+                if p == spf[i] {
+                    mobius[i * p] = 0;
+                    phi[i * p] = phi[i] * p;
+                } else {
+                    mobius[i * p] = -mobius[i];
+                    phi[i * p] = phi[i] * (p - 1);
+                }
+            }
+        }
+
+        Self {
+            primes,
+            spf,
+            mobius,
+            phi,
+        }
+    }
+
+    fn is_prime(&self, x: usize) -> bool {
+        x >= 2 && self.spf[x] == x
+    }
+
+    // Sorted (prime, exponent) pairs of x in O(log x)
+    fn factorize(&self, mut x: usize) -> Vec<(usize, u32)> {
+        let mut ret: Vec<(usize, u32)> = Vec::new();
+
+        while x > 1 {
+            let p = self.spf[x];
+            x /= p;
+
+            match ret.last_mut() {
+                Some(last) if last.0 == p => last.1 += 1,
+                _ => ret.push((p, 1)),
+            }
+        }
+
+        ret
+    }
+}
+
+// Exact values with i64 or i128, modular ones with ModInt
+trait Arithmetic:
+    Copy
+    + From<i64>
+    + std::ops::Add<Output = Self>
+    + std::ops::Sub<Output = Self>
+    + std::ops::Mul<Output = Self>
+{
+}
+
+impl<T> Arithmetic for T where
+    T: Copy
+        + From<i64>
+        + std::ops::Add<Output = T>
+        + std::ops::Sub<Output = T>
+        + std::ops::Mul<Output = T>
+{
+}
+
+fn isqrt(n: u64) -> u64 {
+    let mut ret = (n as f64).sqrt() as u64;
+
+    while ret * ret > n {
+        ret -= 1;
+    }
+
+    while (ret + 1) * (ret + 1) <= n {
+        ret += 1;
+    }
+
+    ret
+}
+
+// Every distinct value of n / i in decreasing order, with O(1) lookup of its position
+struct FloorValues {
+    n: u64,
+    sqrt: u64,
+    vals: Vec<u64>,
+}
+
+impl FloorValues {
+    fn new(n: u64) -> Self {
+        let mut vals = Vec::new();
+        let mut i = 1;
+
+        while i <= n {
+            let v = n / i;
+            vals.push(v);
+            i = n / v + 1;
+        }
+
+        Self {
+            n,
+            sqrt: isqrt(n),
+            vals,
+        }
+    }
+
+    fn index(&self, v: u64) -> usize {
+        if v <= self.sqrt {
+            self.vals.len() - v as usize
+        } else {
+            (self.n / v) as usize - 1
+        }
+    }
+}
+
+// Sum of i^k for 1 <= i <= v, k is at most 2
+fn power_prefix_sum<T: Arithmetic>(v: u64, k: usize) -> T {
+    let mut factors = match k {
+        0 => vec![v],
+        1 => vec![v, v + 1],
+        2 => vec![v, v + 1, 2 * v + 1],
+        _ => panic!("Only powers up to 2 are supported"),
+    };
+
+    // Divide by k + 1 before multiplying so that nothing overflows
+    for d in [2, 3].into_iter().take(k) {
+        let x = factors.iter_mut().find(|x| **x % d == 0).unwrap();
+        *x /= d;
+    }
+
+    factors
+        .into_iter()
+        .fold(T::from(1), |acc, x| acc * T::from(x as i64))
+}
+
+// Reference: https://codeforces.com/blog/entry/91632
+// Sum of p^k over primes p <= v for every floor value v in O(n^(3/4) / log n)
+fn lucy_power_sums<T: Arithmetic>(floor: &FloorValues, primes: &[u64], k: usize) -> Vec<T> {
+    let mut ret = floor
+        .vals
+        .iter()
+        .map(|&v| power_prefix_sum::<T>(v, k) - T::from(1))
+        .collect::<Vec<_>>();
+
+    for &p in primes.iter() {
+        if p * p > floor.n {
+            break;
+        }
+
+        let pk = T::from(p.pow(k as u32) as i64);
+        let base = ret[floor.index(p - 1)];
+
+        // Composites whose smallest prime factor is p are removed
+        for i in 0..floor.vals.len() {
+            let v = floor.vals[i];
+
+            if v < p * p {
+                break;
+            }
+
+            ret[i] = ret[i] - pk * (ret[floor.index(v / p)] - base);
+        }
+    }
+
+    ret
+}
+
+fn prime_count(n: u64) -> u64 {
+    if n < 2 {
+        return 0;
+    }
+
+    let floor = FloorValues::new(n);
+    let primes = LinearSieve::new(floor.sqrt as usize)
+        .primes
+        .iter()
+        .map(|&p| p as u64)
+        .collect::<Vec<_>>();
+
+    lucy_power_sums::<i64>(&floor, &primes, 0)[0] as u64
+}
+
+// Sum of f(i) over 2 <= i <= v whose smallest prime factor is at least primes[j]
+fn process_min_25<T: Arithmetic, F: Fn(u64, u32) -> T>(
+    floor: &FloorValues,
+    primes: &[u64],
+    prime_sum: &[T],
+    prefix: &[T],
+    f: &F,
+    v: u64,
+    j: usize,
+) -> T {
+    let mut ret = prime_sum[floor.index(v)] - prefix[j];
+
+    for i in j..primes.len() {
+        let p = primes[i];
+
+        if p * p > v {
+            break;
+        }
+
+        let mut pe = p;
+        let mut e = 1;
+
+        while pe * p <= v {
+            ret = ret
+                + f(p, e) * process_min_25(floor, primes, prime_sum, prefix, f, v / pe, i + 1)
+                + f(p, e + 1);
+            pe *= p;
+            e += 1;
+        }
+    }
+
+    ret
+}
+
+// Reference: https://codeforces.com/blog/entry/92703
+// Sum of a multiplicative f(i) for 1 <= i <= n, where f(p, e) = f(p^e)
+// f(p) must be the polynomial sum prime_poly[k] * p^k with degree at most 2
+fn min_25<T: Arithmetic, F: Fn(u64, u32) -> T>(n: u64, prime_poly: &[i64], f: F) -> T {
+    if n == 0 {
+        return T::from(0);
+    }
+
+    let floor = FloorValues::new(n);
+    let primes = LinearSieve::new(floor.sqrt as usize)
+        .primes
+        .iter()
+        .map(|&p| p as u64)
+        .collect::<Vec<_>>();
+    let mut prime_sum = vec![T::from(0); floor.vals.len()];
+
+    for (k, &c) in prime_poly.iter().enumerate() {
+        if c == 0 {
+            continue;
+        }
+
+        let sums = lucy_power_sums::<T>(&floor, &primes, k);
+
+        for i in 0..prime_sum.len() {
+            prime_sum[i] = prime_sum[i] + T::from(c) * sums[i];
+        }
+    }
+
+    let mut prefix = vec![T::from(0); primes.len() + 1];
+
+    for i in 0..primes.len() {
+        prefix[i + 1] = prefix[i] + f(primes[i], 1);
+    }
+
+    T::from(1) + process_min_25(&floor, &primes, &prime_sum, &prefix, &f, n, 0)
+}
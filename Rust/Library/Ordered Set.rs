@@ -0,0 +1,598 @@
+use std::ops::{Bound, RangeBounds};
+
+const NIL: usize = usize::MAX;
+
+#[derive(Clone, Debug)]
+struct TreapNode<K, V> {
+    key: K,
+    value: V,
+    priority: u64,
+    left: usize,
+    right: usize,
+    // Copies of the key in this node and in its whole subtree
+    cnt: usize,
+    size: usize,
+}
+
+// Reference: https://en.wikipedia.org/wiki/Treap
+// Ordered map whose subtree sizes answer order statistics in O(log n)
+// Nodes live in an arena without holes, a removal moves the last node into the gap
+#[derive(Clone, Debug)]
+struct OrderedMap<K: Ord, V> {
+    nodes: Vec<TreapNode<K, V>>,
+    root: usize,
+    seed: u64,
+}
+
+// Keys below the start of a range
+fn before_start<K: Ord>(bound: Bound<&K>, key: &K) -> bool {
+    match bound {
+        Bound::Included(s) => key < s,
+        Bound::Excluded(s) => key <= s,
+        Bound::Unbounded => false,
+    }
+}
+
+// Keys not above the end of a range
+fn within_end<K: Ord>(bound: Bound<&K>, key: &K) -> bool {
+    match bound {
+        Bound::Included(e) => key <= e,
+        Bound::Excluded(e) => key < e,
+        Bound::Unbounded => true,
+    }
+}
+
+impl<K: Ord, V> Default for OrderedMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> OrderedMap<K, V> {
+    fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            root: NIL,
+            seed: 88172645463325252,
+        }
+    }
+
+    fn size(&self, x: usize) -> usize {
+        if x == NIL {
+            0
+        } else {
+            self.nodes[x].size
+        }
+    }
+
+    fn update(&mut self, x: usize) {
+        let node = &self.nodes[x];
+        self.nodes[x].size = node.cnt + self.size(node.left) + self.size(node.right);
+    }
+
+    // Splits into keys before `key` and the rest, `key` goes left when inclusive
+    fn split(&mut self, x: usize, key: &K, inclusive: bool) -> (usize, usize) {
+        if x == NIL {
+            return (NIL, NIL);
+        }
+
+        let left = if inclusive {
+            self.nodes[x].key <= *key
+        } else {
+            self.nodes[x].key < *key
+        };
+
+        if left {
+            let (l, r) = self.split(self.nodes[x].right, key, inclusive);
+            self.nodes[x].right = l;
+            self.update(x);
+
+            (x, r)
+        } else {
+            let (l, r) = self.split(self.nodes[x].left, key, inclusive);
+            self.nodes[x].left = r;
+            self.update(x);
+
+            (l, x)
+        }
+    }
+
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        if a == NIL {
+            return b;
+        }
+
+        if b == NIL {
+            return a;
+        }
+
+        if self.nodes[a].priority > self.nodes[b].priority {
+            let right = self.merge(self.nodes[a].right, b);
+            self.nodes[a].right = right;
+            self.update(a);
+
+            a
+        } else {
+            let left = self.merge(a, self.nodes[b].left);
+            self.nodes[b].left = left;
+            self.update(b);
+
+            b
+        }
+    }
+
+    fn find(&self, key: &K) -> usize {
+        let mut x = self.root;
+
+        while x != NIL && self.nodes[x].key != *key {
+            x = if *key < self.nodes[x].key {
+                self.nodes[x].left
+            } else {
+                self.nodes[x].right
+            };
+        }
+
+        x
+    }
+
+    // Points the link from parent towards key at x, the root when parent is NIL
+    fn set_child(&mut self, parent: usize, key: &K, x: usize) {
+        if parent == NIL {
+            self.root = x;
+        } else if *key < self.nodes[parent].key {
+            self.nodes[parent].left = x;
+        } else {
+            self.nodes[parent].right = x;
+        }
+    }
+
+    // Adds a node with cnt copies, the key must be absent
+    // Only the subtree the new node takes over is split, which keeps the walk to one path
+    fn insert_node(&mut self, key: K, value: V, cnt: usize) -> usize {
+        self.seed ^= self.seed << 7;
+        self.seed ^= self.seed >> 9;
+
+        let priority = self.seed;
+        let mut parent = NIL;
+        let mut x = self.root;
+
+        while x != NIL && self.nodes[x].priority > priority {
+            self.nodes[x].size += cnt;
+            parent = x;
+            x = if key < self.nodes[x].key {
+                self.nodes[x].left
+            } else {
+                self.nodes[x].right
+            };
+        }
+
+        let (l, r) = self.split(x, &key, false);
+        let idx = self.nodes.len();
+
+        self.set_child(parent, &key, idx);
+        self.nodes.push(TreapNode {
+            key,
+            value,
+            priority,
+            left: l,
+            right: r,
+            cnt,
+            size: 0,
+        });
+        self.update(idx);
+
+        idx
+    }
+
+    // Detaches the node of a present key and returns it
+    fn remove_node(&mut self, key: &K) -> TreapNode<K, V> {
+        let cnt = self.nodes[self.find(key)].cnt;
+        let mut parent = NIL;
+        let mut x = self.root;
+
+        while self.nodes[x].key != *key {
+            self.nodes[x].size -= cnt;
+            parent = x;
+            x = if *key < self.nodes[x].key {
+                self.nodes[x].left
+            } else {
+                self.nodes[x].right
+            };
+        }
+
+        let merged = self.merge(self.nodes[x].left, self.nodes[x].right);
+        self.set_child(parent, key, merged);
+
+        // The last node of the arena moves to x, its parent has to follow
+        let last = self.nodes.len() - 1;
+
+        if x != last {
+            let mut parent = NIL;
+            let mut y = self.root;
+
+            while y != last {
+                parent = y;
+                y = if self.nodes[last].key < self.nodes[y].key {
+                    self.nodes[y].left
+                } else {
+                    self.nodes[y].right
+                };
+            }
+
+            let last_node = &self.nodes[last];
+
+            if parent == NIL {
+                self.root = x;
+            } else if last_node.key < self.nodes[parent].key {
+                self.nodes[parent].left = x;
+            } else {
+                self.nodes[parent].right = x;
+            }
+        }
+
+        self.nodes.swap_remove(x)
+    }
+
+    // Changes the copies of a present key by one along its path
+    fn shift_count(&mut self, key: &K, grow: bool) {
+        let mut x = self.root;
+
+        loop {
+            let node = &mut self.nodes[x];
+
+            if grow {
+                node.size += 1;
+            } else {
+                node.size -= 1;
+            }
+
+            if node.key == *key {
+                if grow {
+                    node.cnt += 1;
+                } else {
+                    node.cnt -= 1;
+                }
+
+                return;
+            }
+
+            x = if *key < node.key {
+                node.left
+            } else {
+                node.right
+            };
+        }
+    }
+
+    // Copies whose keys satisfy pred, which must hold on a prefix of the keys
+    fn count_by(&self, pred: impl Fn(&K) -> bool) -> usize {
+        let mut ret = 0;
+        let mut x = self.root;
+
+        while x != NIL {
+            let node = &self.nodes[x];
+
+            if pred(&node.key) {
+                ret += self.size(node.left) + node.cnt;
+                x = node.right;
+            } else {
+                x = node.left;
+            }
+        }
+
+        ret
+    }
+
+    // First node whose key fails pred, which must hold on a prefix of the keys
+    fn first_by(&self, pred: impl Fn(&K) -> bool) -> usize {
+        let mut ret = NIL;
+        let mut x = self.root;
+
+        while x != NIL {
+            if pred(&self.nodes[x].key) {
+                x = self.nodes[x].right;
+            } else {
+                ret = x;
+                x = self.nodes[x].left;
+            }
+        }
+
+        ret
+    }
+
+    // Last node whose key satisfies pred, which must hold on a prefix of the keys
+    fn last_by(&self, pred: impl Fn(&K) -> bool) -> usize {
+        let mut ret = NIL;
+        let mut x = self.root;
+
+        while x != NIL {
+            if pred(&self.nodes[x].key) {
+                ret = x;
+                x = self.nodes[x].right;
+            } else {
+                x = self.nodes[x].left;
+            }
+        }
+
+        ret
+    }
+
+    // Node holding the k-th copy from 0
+    fn kth_node(&self, mut k: usize) -> usize {
+        let mut x = self.root;
+
+        while x != NIL {
+            let left = self.size(self.nodes[x].left);
+
+            if k < left {
+                x = self.nodes[x].left;
+            } else if k < left + self.nodes[x].cnt {
+                return x;
+            } else {
+                k -= left + self.nodes[x].cnt;
+                x = self.nodes[x].right;
+            }
+        }
+
+        NIL
+    }
+
+    fn entry(&self, x: usize) -> Option<(&K, &V)> {
+        if x == NIL {
+            None
+        } else {
+            Some((&self.nodes[x].key, &self.nodes[x].value))
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.root == NIL
+    }
+
+    // Returns the old value when the key was present
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let x = self.find(&key);
+
+        if x != NIL {
+            return Some(std::mem::replace(&mut self.nodes[x].value, value));
+        }
+
+        self.insert_node(key, value, 1);
+        None
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        self.entry(self.find(key)).map(|(_, v)| v)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let x = self.find(key);
+
+        if x == NIL {
+            None
+        } else {
+            Some(&mut self.nodes[x].value)
+        }
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.find(key) != NIL
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        if self.find(key) == NIL {
+            return None;
+        }
+
+        Some(self.remove_node(key).value)
+    }
+
+    // k-th smallest key from 0
+    fn kth(&self, k: usize) -> Option<(&K, &V)> {
+        self.entry(self.kth_node(k))
+    }
+
+    // Number of keys smaller than key
+    fn rank(&self, key: &K) -> usize {
+        self.count_by(|k| k < key)
+    }
+
+    fn range_count(&self, range: impl RangeBounds<K>) -> usize {
+        let below = self.count_by(|k| before_start(range.start_bound(), k));
+        let upto = self.count_by(|k| within_end(range.end_bound(), k));
+
+        upto.saturating_sub(below)
+    }
+
+    // First key not less than key
+    fn lower_bound(&self, key: &K) -> Option<(&K, &V)> {
+        self.entry(self.first_by(|k| k < key))
+    }
+
+    // First key greater than key
+    fn upper_bound(&self, key: &K) -> Option<(&K, &V)> {
+        self.entry(self.first_by(|k| k <= key))
+    }
+
+    fn range(&self, range: impl RangeBounds<K>) -> Range<'_, K, V> {
+        let mut stack = Vec::new();
+        let mut x = self.root;
+
+        while x != NIL {
+            if before_start(range.start_bound(), &self.nodes[x].key) {
+                x = self.nodes[x].right;
+            } else {
+                stack.push(x);
+                x = self.nodes[x].left;
+            }
+        }
+
+        let last = self.last_by(|k| within_end(range.end_bound(), k));
+
+        let end = range.end_bound();
+
+        if matches!(stack.last(), Some(&top) if !within_end(end, &self.nodes[top].key)) {
+            stack.clear();
+        }
+
+        Range {
+            map: self,
+            stack,
+            last,
+        }
+    }
+
+    fn iter(&self) -> Range<'_, K, V> {
+        self.range(..)
+    }
+}
+
+// In-order walk from the first node of the range to its last one
+struct Range<'a, K: Ord, V> {
+    map: &'a OrderedMap<K, V>,
+    stack: Vec<usize>,
+    last: usize,
+}
+
+impl<'a, K: Ord, V> Range<'a, K, V> {
+    fn next_node(&mut self) -> Option<usize> {
+        let x = self.stack.pop()?;
+
+        if x == self.last {
+            self.stack.clear();
+        } else {
+            let mut y = self.map.nodes[x].right;
+
+            while y != NIL {
+                self.stack.push(y);
+                y = self.map.nodes[y].left;
+            }
+        }
+
+        Some(x)
+    }
+}
+
+impl<'a, K: Ord, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.next_node()?;
+        let node = &self.map.nodes[x];
+
+        Some((&node.key, &node.value))
+    }
+}
+
+// Multiset where kth, rank and range_count count every copy
+#[derive(Clone, Debug)]
+struct OrderedMultiSet<T: Ord> {
+    map: OrderedMap<T, ()>,
+}
+
+impl<T: Ord> Default for OrderedMultiSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> OrderedMultiSet<T> {
+    fn new() -> Self {
+        Self {
+            map: OrderedMap::new(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    fn insert(&mut self, val: T) {
+        if self.map.find(&val) == NIL {
+            self.map.insert_node(val, (), 1);
+        } else {
+            self.map.shift_count(&val, true);
+        }
+    }
+
+    // Removes one copy, returns false when there was none
+    fn remove_one(&mut self, val: &T) -> bool {
+        match self.count(val) {
+            0 => false,
+            1 => {
+                self.map.remove_node(val);
+                true
+            }
+            _ => {
+                self.map.shift_count(val, false);
+                true
+            }
+        }
+    }
+
+    // Removes every copy and returns how many there were
+    fn remove_all(&mut self, val: &T) -> usize {
+        if self.map.find(val) == NIL {
+            return 0;
+        }
+
+        self.map.remove_node(val).cnt
+    }
+
+    fn count(&self, val: &T) -> usize {
+        let x = self.map.find(val);
+
+        if x == NIL {
+            0
+        } else {
+            self.map.nodes[x].cnt
+        }
+    }
+
+    fn contains(&self, val: &T) -> bool {
+        self.map.contains_key(val)
+    }
+
+    // k-th smallest copy from 0
+    fn kth(&self, k: usize) -> Option<&T> {
+        self.map.kth(k).map(|(k, _)| k)
+    }
+
+    // Number of copies smaller than val
+    fn rank(&self, val: &T) -> usize {
+        self.map.rank(val)
+    }
+
+    fn range_count(&self, range: impl RangeBounds<T>) -> usize {
+        self.map.range_count(range)
+    }
+
+    fn lower_bound(&self, val: &T) -> Option<&T> {
+        self.map.lower_bound(val).map(|(k, _)| k)
+    }
+
+    fn upper_bound(&self, val: &T) -> Option<&T> {
+        self.map.upper_bound(val).map(|(k, _)| k)
+    }
+
+    // Every copy in the range, equal values repeated
+    fn range(&self, range: impl RangeBounds<T>) -> impl Iterator<Item = &T> + '_ {
+        let nodes = &self.map.nodes;
+        let mut range = self.map.range(range);
+
+        std::iter::from_fn(move || range.next_node())
+            .flat_map(move |x| std::iter::repeat_n(&nodes[x].key, nodes[x].cnt))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.range(..)
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+// Node 0 is the imaginary root (length -1), node 1 is the empty root (length 0)
+#[derive(Clone)]
+struct Node<T> {
+    len: i64,
+    suff_link: usize,
+    diff: i64,
+    series_link: usize,
+    first_end: usize,
+    next: BTreeMap<T, usize>,
+}
+
+impl<T> Node<T> {
+    fn new(len: i64, suff_link: usize) -> Self {
+        Self {
+            len,
+            suff_link,
+            diff: 0,
+            series_link: 0,
+            first_end: 0,
+            next: BTreeMap::new(),
+        }
+    }
+}
+
+// Reference: https://www.secmem.org/blog/2019/05/17/Palindromic-Tree/
+// Reference: https://arxiv.org/abs/1506.04862
+struct PalindromicTree<T> {
+    nodes: Vec<Node<T>>,
+    text: Vec<T>,
+    table: Vec<u64>,
+    last_suff: usize,
+}
+
+impl<T: Ord + Copy> PalindromicTree<T> {
+    fn new() -> Self {
+        Self {
+            nodes: vec![Node::new(-1, 0), Node::new(0, 0)],
+            text: Vec::new(),
+            table: vec![0, 0],
+            last_suff: 1,
+        }
+    }
+
+    fn from_slice(s: &[T]) -> Self {
+        let mut tree = Self::new();
+
+        for &c in s {
+            tree.push(c);
+        }
+
+        tree
+    }
+
+    fn get_link(&self, mut cur: usize, idx: usize) -> usize {
+        loop {
+            let len = self.nodes[cur].len;
+
+            if idx as i64 > len && self.text[idx - (len + 1) as usize] == self.text[idx] {
+                return cur;
+            }
+
+            cur = self.nodes[cur].suff_link;
+        }
+    }
+
+    // Appends c and returns the node of the longest palindromic suffix
+    fn push(&mut self, c: T) -> usize {
+        let idx = self.text.len();
+        self.text.push(c);
+
+        let cur = self.get_link(self.last_suff, idx);
+
+        if let Some(&next) = self.nodes[cur].next.get(&c) {
+            // A palindrome first occurs as the longest palindromic suffix, so after a reset
+            // its first end in the current text is where table leaves 0
+            if self.table[next] == 0 {
+                self.nodes[next].first_end = idx + 1;
+            }
+
+            self.last_suff = next;
+            self.table[next] += 1;
+            return next;
+        }
+
+        let len = self.nodes[cur].len + 2;
+        let suff_link = if len == 1 {
+            1
+        } else {
+            let link = self.get_link(self.nodes[cur].suff_link, idx);
+            self.nodes[link].next[&c]
+        };
+
+        let mut node = Node::new(len, suff_link);
+        node.diff = len - self.nodes[suff_link].len;
+        node.series_link = if node.diff == self.nodes[suff_link].diff {
+            self.nodes[suff_link].series_link
+        } else {
+            suff_link
+        };
+        node.first_end = idx + 1;
+
+        let next = self.nodes.len();
+        self.nodes.push(node);
+        self.nodes[cur].next.insert(c, next);
+        self.table.push(1);
+        self.last_suff = next;
+
+        next
+    }
+
+    // Starts a new string while keeping the nodes, so several strings share one tree
+    // Occurrence counts restart from 0 for the new string
+    fn reset_text(&mut self) {
+        self.text.clear();
+        self.table.iter_mut().for_each(|x| *x = 0);
+        self.last_suff = 1;
+    }
+
+    fn count_distinct(&self) -> usize {
+        self.nodes.len() - 2
+    }
+
+    // Occurrence count of every node in the text pushed so far
+    fn occurrences(&self) -> Vec<u64> {
+        let mut ret = self.table.clone();
+
+        for i in (2..self.nodes.len()).rev() {
+            ret[self.nodes[i].suff_link] += ret[i];
+        }
+
+        ret
+    }
+
+    // Position of the first occurrence of the palindrome at node in the current text
+    // Only meaningful for nodes that occur in the current text, see occurrences
+    fn palindrome(&self, node: usize) -> Range<usize> {
+        let end = self.nodes[node].first_end;
+        end - self.nodes[node].len as usize..end
+    }
+}
+
+// Splits s into the minimum number of palindromes in O(n log n) with series links
+fn min_palindromic_factorization<T: Ord + Copy>(s: &[T]) -> Vec<Range<usize>> {
+    let n = s.len();
+    let mut tree = PalindromicTree::new();
+    let mut dp = vec![(usize::MAX, 0); n + 1];
+    let mut series_ans = vec![(usize::MAX, 0); n + 2];
+
+    dp[0] = (0, 0);
+
+    for i in 1..=n {
+        let mut v = tree.push(s[i - 1]);
+
+        if series_ans.len() < tree.nodes.len() {
+            series_ans.resize(tree.nodes.len(), (usize::MAX, 0));
+        }
+
+        while tree.nodes[v].len > 0 {
+            let node = &tree.nodes[v];
+            let start = i - (tree.nodes[node.series_link].len + node.diff) as usize;
+
+            series_ans[v] = (dp[start].0, start);
+
+            if node.diff == tree.nodes[node.suff_link].diff {
+                series_ans[v] = series_ans[v].min(series_ans[node.suff_link]);
+            }
+
+            if series_ans[v].0 + 1 < dp[i].0 {
+                dp[i] = (series_ans[v].0 + 1, series_ans[v].1);
+            }
+
+            v = node.series_link;
+        }
+    }
+
+    let mut ret = Vec::new();
+    let mut i = n;
+
+    while i > 0 {
+        ret.push(dp[i].1..i);
+        i = dp[i].1;
+    }
+
+    ret.reverse();
+    ret
+}
+
+// Returns (number of distinct common palindromes, number of pairs of equal palindromic occurrences)
+fn common_palindromes<T: Ord + Copy>(s: &[T], t: &[T]) -> (usize, u64) {
+    let mut tree = PalindromicTree::new();
+
+    for &c in s {
+        tree.push(c);
+    }
+
+    let cnt_s = tree.occurrences();
+    tree.reset_text();
+
+    for &c in t {
+        tree.push(c);
+    }
+
+    let cnt_t = tree.occurrences();
+    let mut distinct = 0;
+    let mut pairs = 0;
+
+    for i in 2..cnt_s.len() {
+        if cnt_s[i] > 0 && cnt_t[i] > 0 {
+            distinct += 1;
+            pairs += cnt_s[i] * cnt_t[i];
+        }
+    }
+
+    (distinct, pairs)
+}
@@ -0,0 +1,309 @@
+use std::cmp::Ordering;
+use std::ops::{Add, Mul, Range, Sub};
+
+struct Rng([u64; 4]);
+
+impl Rng {
+    fn split_mix(v: u64) -> u64 {
+        let mut z = v.wrapping_add(0x9e3779b97f4a7c15);
+
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn new() -> Self {
+        let mut seed = 0;
+        unsafe { std::arch::x86_64::_rdrand64_step(&mut seed) };
+
+        let mut prev = seed;
+
+        Self(std::array::from_fn(|_| {
+            prev = Self::split_mix(prev);
+            prev
+        }))
+    }
+
+    fn next(&mut self, n: u64) -> u64 {
+        let [x, y, z, c] = &mut self.0;
+        let t = x.wrapping_shl(58).wrapping_add(*c);
+
+        *c = *x >> 6;
+        *x = x.wrapping_add(t);
+
+        if *x < t {
+            *c += 1;
+        }
+
+        *z = z.wrapping_mul(6906969069).wrapping_add(1234567);
+        *y ^= y.wrapping_shl(13);
+        *y ^= *y >> 17;
+        *y ^= y.wrapping_shl(43);
+
+        let base = x.wrapping_add(*y).wrapping_add(*z);
+        ((base as u128 * n as u128) >> 64) as u64
+    }
+}
+
+trait HashValue: Copy + Eq + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    fn from_u64(val: u64) -> Self;
+
+    // Bases are drawn at runtime so that fixed anti-hash tests cannot target them
+    fn random_base(rng: &mut Rng) -> Self;
+}
+
+const MOD_61: u64 = (1 << 61) - 1;
+
+// Single hash modulo the Mersenne prime 2^61 - 1
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+struct Mersenne61(u64);
+
+impl Add for Mersenne61 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let ret = self.0 + other.0;
+        Self(if ret >= MOD_61 { ret - MOD_61 } else { ret })
+    }
+}
+
+impl Sub for Mersenne61 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(if self.0 >= other.0 {
+            self.0 - other.0
+        } else {
+            self.0 + MOD_61 - other.0
+        })
+    }
+}
+
+impl Mul for Mersenne61 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let t = self.0 as u128 * other.0 as u128;
+        let ret = (t >> 61) as u64 + (t as u64 & MOD_61);
+        Self(if ret >= MOD_61 { ret - MOD_61 } else { ret })
+    }
+}
+
+impl HashValue for Mersenne61 {
+    fn from_u64(val: u64) -> Self {
+        Self(val % MOD_61)
+    }
+
+    fn random_base(rng: &mut Rng) -> Self {
+        Self(rng.next(MOD_61 - 1000) + 500)
+    }
+}
+
+const MOD_A: u64 = 1_000_000_007;
+const MOD_B: u64 = 998_244_353;
+
+// Pair of hashes modulo two 30-bit primes
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+struct DoubleMod(u64, u64);
+
+impl Add for DoubleMod {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self((self.0 + other.0) % MOD_A, (self.1 + other.1) % MOD_B)
+    }
+}
+
+impl Sub for DoubleMod {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(
+            (self.0 + MOD_A - other.0) % MOD_A,
+            (self.1 + MOD_B - other.1) % MOD_B,
+        )
+    }
+}
+
+impl Mul for DoubleMod {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self(self.0 * other.0 % MOD_A, self.1 * other.1 % MOD_B)
+    }
+}
+
+impl HashValue for DoubleMod {
+    fn from_u64(val: u64) -> Self {
+        Self(val % MOD_A, val % MOD_B)
+    }
+
+    fn random_base(rng: &mut Rng) -> Self {
+        Self(rng.next(MOD_A - 1000) + 500, rng.next(MOD_B - 1000) + 500)
+    }
+}
+
+fn pow_hash<H: HashValue>(mut base: H, mut exp: usize) -> H {
+    let mut ret = H::from_u64(1);
+
+    while exp > 0 {
+        if exp % 2 == 1 {
+            ret = ret * base;
+        }
+
+        base = base * base;
+        exp /= 2;
+    }
+
+    ret
+}
+
+struct RollingHash<H> {
+    text: Vec<u64>,
+    base: H,
+    prefix: Vec<H>,
+    power: Vec<H>,
+}
+
+impl<H: HashValue> RollingHash<H> {
+    // Strings whose hashes are compared with each other must share the same base
+    fn new<T: Copy + Into<u64>>(s: &[T], base: H) -> Self {
+        let text = s.iter().map(|&c| c.into() + 1).collect::<Vec<_>>();
+        let mut prefix = vec![H::from_u64(0); text.len() + 1];
+        let mut power = vec![H::from_u64(1); text.len() + 1];
+
+        for i in 0..text.len() {
+            prefix[i + 1] = prefix[i] * base + H::from_u64(text[i]);
+            power[i + 1] = power[i] * base;
+        }
+
+        Self {
+            text,
+            base,
+            prefix,
+            power,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    fn pow(&self, exp: usize) -> H {
+        if exp < self.power.len() {
+            self.power[exp]
+        } else {
+            pow_hash(self.base, exp)
+        }
+    }
+
+    fn get(&self, range: Range<usize>) -> H {
+        self.prefix[range.end] - self.prefix[range.start] * self.power[range.end - range.start]
+    }
+
+    // Hash of left followed by a string of length right_len hashing to right
+    fn concat(&self, left: H, right: H, right_len: usize) -> H {
+        left * self.pow(right_len) + right
+    }
+
+    // Longest common prefix of self[i..] and other[j..] by binary search
+    fn lcp(&self, i: usize, other: &RollingHash<H>, j: usize) -> usize {
+        let mut left = 0;
+        let mut right = (self.len() - i).min(other.len() - j) + 1;
+
+        while left + 1 < right {
+            let mid = (left + right) / 2;
+
+            if self.get(i..i + mid) == other.get(j..j + mid) {
+                left = mid;
+            } else {
+                right = mid;
+            }
+        }
+
+        left
+    }
+
+    fn compare(&self, a: Range<usize>, other: &RollingHash<H>, b: Range<usize>) -> Ordering {
+        let len_a = a.end - a.start;
+        let len_b = b.end - b.start;
+        let common = self.lcp(a.start, other, b.start).min(len_a).min(len_b);
+
+        if common == len_a.min(len_b) {
+            len_a.cmp(&len_b)
+        } else {
+            self.text[a.start + common].cmp(&other.text[b.start + common])
+        }
+    }
+}
+
+struct PalindromeHash<H> {
+    forward: RollingHash<H>,
+    backward: RollingHash<H>,
+}
+
+impl<H: HashValue> PalindromeHash<H> {
+    fn new<T: Copy + Into<u64>>(s: &[T], base: H) -> Self {
+        let reversed = s.iter().rev().copied().collect::<Vec<_>>();
+
+        Self {
+            forward: RollingHash::new(s, base),
+            backward: RollingHash::new(&reversed, base),
+        }
+    }
+
+    fn is_palindrome(&self, range: Range<usize>) -> bool {
+        let n = self.forward.len();
+        self.forward.get(range.clone()) == self.backward.get(n - range.end..n - range.start)
+    }
+}
+
+struct GridHash<H> {
+    prefix: Vec<Vec<H>>,
+    power_row: Vec<H>,
+    power_col: Vec<H>,
+}
+
+impl<H: HashValue> GridHash<H> {
+    // Rows are combined with base_row and columns with base_col, the two must differ
+    fn new<T: Copy + Into<u64>>(grid: &[Vec<T>], base_row: H, base_col: H) -> Self {
+        let n = grid.len();
+        let m = if n == 0 { 0 } else { grid[0].len() };
+        let zero = H::from_u64(0);
+        let mut prefix = vec![vec![zero; m + 1]; n + 1];
+        let mut power_row = vec![H::from_u64(1); n + 1];
+        let mut power_col = vec![H::from_u64(1); m + 1];
+
+        for i in 0..n {
+            power_row[i + 1] = power_row[i] * base_row;
+        }
+
+        for j in 0..m {
+            power_col[j + 1] = power_col[j] * base_col;
+        }
+
+        for i in 0..n {
+            for j in 0..m {
+                prefix[i + 1][j + 1] = prefix[i][j + 1] * base_row + prefix[i + 1][j] * base_col
+                    - prefix[i][j] * base_row * base_col
+                    + H::from_u64(grid[i][j].into() + 1);
+            }
+        }
+
+        Self {
+            prefix,
+            power_row,
+            power_col,
+        }
+    }
+
+    fn get(&self, rows: Range<usize>, cols: Range<usize>) -> H {
+        let h = self.power_row[rows.end - rows.start];
+        let w = self.power_col[cols.end - cols.start];
+
+        self.prefix[rows.end][cols.end]
+            - self.prefix[rows.start][cols.end] * h
+            - self.prefix[rows.end][cols.start] * w
+            + self.prefix[rows.start][cols.start] * h * w
+    }
+}
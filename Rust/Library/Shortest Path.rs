@@ -0,0 +1,547 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet, VecDeque};
+
+// Distance of an unreachable vertex and of one behind a negative cycle
+const DIST_INF: i64 = i64::MAX;
+const DIST_NEG_INF: i64 = i64::MIN;
+
+// Compressed sparse rows, the edges of u are the slots start[u]..start[u + 1]
+// id keeps the index of the edge in the input, which path reconstruction reports
+struct CsrGraph {
+    start: Vec<usize>,
+    from: Vec<usize>,
+    to: Vec<usize>,
+    weight: Vec<i64>,
+    id: Vec<usize>,
+}
+
+impl CsrGraph {
+    // Directed edges (u, v, w)
+    fn new(n: usize, edges: &[(usize, usize, i64)]) -> Self {
+        let arcs = edges
+            .iter()
+            .enumerate()
+            .map(|(idx, &(u, v, w))| (u, v, w, idx))
+            .collect::<Vec<_>>();
+
+        Self::from_arcs(n, &arcs)
+    }
+
+    // Both directions of every edge share its index
+    fn undirected(n: usize, edges: &[(usize, usize, i64)]) -> Self {
+        let mut arcs = Vec::with_capacity(2 * edges.len());
+
+        for (idx, &(u, v, w)) in edges.iter().enumerate() {
+            arcs.push((u, v, w, idx));
+            arcs.push((v, u, w, idx));
+        }
+
+        Self::from_arcs(n, &arcs)
+    }
+
+    fn from_arcs(n: usize, arcs: &[(usize, usize, i64, usize)]) -> Self {
+        let mut start = vec![0; n + 1];
+
+        for &(u, _, _, _) in arcs.iter() {
+            start[u + 1] += 1;
+        }
+
+        for i in 0..n {
+            start[i + 1] += start[i];
+        }
+
+        let mut pos = start.clone();
+        let mut from = vec![0; arcs.len()];
+        let mut to = vec![0; arcs.len()];
+        let mut weight = vec![0; arcs.len()];
+        let mut id = vec![0; arcs.len()];
+
+        for &(u, v, w, idx) in arcs.iter() {
+            let slot = pos[u];
+            pos[u] += 1;
+
+            from[slot] = u;
+            to[slot] = v;
+            weight[slot] = w;
+            id[slot] = idx;
+        }
+
+        Self {
+            start,
+            from,
+            to,
+            weight,
+            id,
+        }
+    }
+
+    fn n(&self) -> usize {
+        self.start.len() - 1
+    }
+
+    fn edges(&self, u: usize) -> std::ops::Range<usize> {
+        self.start[u]..self.start[u + 1]
+    }
+}
+
+// parent[v] is the previous vertex and the index of the edge that reached v
+// A source has no parent, so does a vertex at DIST_INF or DIST_NEG_INF
+#[derive(Clone, Debug)]
+struct ShortestPaths {
+    dist: Vec<i64>,
+    parent: Vec<Option<(usize, usize)>>,
+}
+
+impl ShortestPaths {
+    fn new(n: usize) -> Self {
+        Self {
+            dist: vec![DIST_INF; n],
+            parent: vec![None; n],
+        }
+    }
+
+    fn reachable(&self, target: usize) -> bool {
+        self.dist[target] != DIST_INF && self.dist[target] != DIST_NEG_INF
+    }
+
+    // Vertices from the closest source to target
+    fn path(&self, target: usize) -> Option<Vec<usize>> {
+        if !self.reachable(target) {
+            return None;
+        }
+
+        let mut ret = vec![target];
+        let mut vertex = target;
+
+        while let Some((prev, _)) = self.parent[vertex] {
+            ret.push(prev);
+            vertex = prev;
+        }
+
+        ret.reverse();
+        Some(ret)
+    }
+
+    // Edge indices along the same path
+    fn path_edges(&self, target: usize) -> Option<Vec<usize>> {
+        if !self.reachable(target) {
+            return None;
+        }
+
+        let mut ret = Vec::new();
+        let mut vertex = target;
+
+        while let Some((prev, edge)) = self.parent[vertex] {
+            ret.push(edge);
+            vertex = prev;
+        }
+
+        ret.reverse();
+        Some(ret)
+    }
+}
+
+// Reference: https://github.com/iwiwi/radix-heap
+// Monotone priority queue, a popped key never exceeds the keys pushed afterwards
+struct RadixHeap<T> {
+    buckets: Vec<Vec<(u64, T)>>,
+    last: u64,
+    len: usize,
+}
+
+impl<T> RadixHeap<T> {
+    fn new() -> Self {
+        Self {
+            buckets: (0..65).map(|_| Vec::new()).collect(),
+            last: 0,
+            len: 0,
+        }
+    }
+
+    fn bucket(&self, key: u64) -> usize {
+        64 - (key ^ self.last).leading_zeros() as usize
+    }
+
+    fn push(&mut self, key: u64, val: T) {
+        debug_assert!(
+            key >= self.last,
+            "Keys must not go below the last popped key"
+        );
+
+        let idx = self.bucket(key);
+        self.buckets[idx].push((key, val));
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<(u64, T)> {
+        if self.len == 0 {
+            return None;
+        }
+
+        // Everything in the first nonempty bucket moves closer to the new minimum
+        if self.buckets[0].is_empty() {
+            let idx = (1..65).find(|&i| !self.buckets[i].is_empty()).unwrap();
+            let bucket = std::mem::take(&mut self.buckets[idx]);
+            self.last = bucket.iter().map(|&(key, _)| key).min().unwrap();
+
+            for (key, val) in bucket {
+                let idx = self.bucket(key);
+                self.buckets[idx].push((key, val));
+            }
+        }
+
+        self.len -= 1;
+        self.buckets[0].pop()
+    }
+}
+
+impl CsrGraph {
+    // Dijkstra from (source, initial distance) pairs, cost(u, slot) gives the weight of a slot
+    // or None for a slot that must not be used, weights must be nonnegative
+    fn process_dijkstra(
+        &self,
+        sources: &[(usize, i64)],
+        cost: impl Fn(usize, usize) -> Option<i64>,
+    ) -> ShortestPaths {
+        let mut ret = ShortestPaths::new(self.n());
+        let mut heap = BinaryHeap::new();
+
+        for &(s, d) in sources.iter() {
+            if d < ret.dist[s] {
+                ret.dist[s] = d;
+                heap.push(Reverse((d, s)));
+            }
+        }
+
+        while let Some(Reverse((d, u))) = heap.pop() {
+            if d > ret.dist[u] {
+                continue;
+            }
+
+            for slot in self.edges(u) {
+                let Some(w) = cost(u, slot) else {
+                    continue;
+                };
+
+                let v = self.to[slot];
+                let next = d + w;
+
+                if next < ret.dist[v] {
+                    ret.dist[v] = next;
+                    ret.parent[v] = Some((u, self.id[slot]));
+                    heap.push(Reverse((next, v)));
+                }
+            }
+        }
+
+        ret
+    }
+
+    // Nonnegative weights in O(m log n)
+    fn dijkstra(&self, sources: &[usize]) -> ShortestPaths {
+        let sources = sources.iter().map(|&s| (s, 0)).collect::<Vec<_>>();
+        self.process_dijkstra(&sources, |_, slot| Some(self.weight[slot]))
+    }
+
+    // Nonnegative weights in O(m + n log C) where C is the largest distance
+    fn dijkstra_radix(&self, sources: &[usize]) -> ShortestPaths {
+        let mut ret = ShortestPaths::new(self.n());
+        let mut heap = RadixHeap::new();
+
+        for &s in sources.iter() {
+            ret.dist[s] = 0;
+            heap.push(0, s);
+        }
+
+        while let Some((d, u)) = heap.pop() {
+            if d as i64 > ret.dist[u] {
+                continue;
+            }
+
+            for slot in self.edges(u) {
+                let v = self.to[slot];
+                let next = d as i64 + self.weight[slot];
+
+                if next < ret.dist[v] {
+                    ret.dist[v] = next;
+                    ret.parent[v] = Some((u, self.id[slot]));
+                    heap.push(next as u64, v);
+                }
+            }
+        }
+
+        ret
+    }
+
+    // Weights 0 and 1 only in O(n + m)
+    fn bfs01(&self, sources: &[usize]) -> ShortestPaths {
+        let mut ret = ShortestPaths::new(self.n());
+        let mut deque = VecDeque::new();
+
+        for &s in sources.iter() {
+            ret.dist[s] = 0;
+            deque.push_back(s);
+        }
+
+        while let Some(u) = deque.pop_front() {
+            for slot in self.edges(u) {
+                let v = self.to[slot];
+                let w = self.weight[slot];
+                debug_assert!(w == 0 || w == 1, "0-1 BFS needs weights 0 and 1");
+
+                if ret.dist[u] + w < ret.dist[v] {
+                    ret.dist[v] = ret.dist[u] + w;
+                    ret.parent[v] = Some((u, self.id[slot]));
+
+                    if w == 0 {
+                        deque.push_front(v);
+                    } else {
+                        deque.push_back(v);
+                    }
+                }
+            }
+        }
+
+        ret
+    }
+
+    // Any weights in O(nm), a vertex reachable through a negative cycle gets DIST_NEG_INF
+    fn bellman_ford(&self, sources: &[usize]) -> ShortestPaths {
+        let n = self.n();
+        let mut ret = ShortestPaths::new(n);
+
+        for &s in sources.iter() {
+            ret.dist[s] = 0;
+        }
+
+        for _ in 1..n {
+            if self.relax_all(&mut ret).is_none() {
+                return ret;
+            }
+        }
+
+        // Whatever still improves lies on or behind a negative cycle
+        let mut stack = Vec::new();
+
+        for slot in 0..self.to.len() {
+            let (u, v) = (self.from[slot], self.to[slot]);
+
+            if ret.dist[u] != DIST_INF && ret.dist[u] + self.weight[slot] < ret.dist[v] {
+                stack.push(v);
+            }
+        }
+
+        while let Some(u) = stack.pop() {
+            if ret.dist[u] == DIST_NEG_INF {
+                continue;
+            }
+
+            ret.dist[u] = DIST_NEG_INF;
+            ret.parent[u] = None;
+
+            for slot in self.edges(u) {
+                stack.push(self.to[slot]);
+            }
+        }
+
+        ret
+    }
+
+    // One round over every edge, returns the last vertex whose distance improved
+    fn relax_all(&self, paths: &mut ShortestPaths) -> Option<usize> {
+        let mut ret = None;
+
+        for slot in 0..self.to.len() {
+            let (u, v) = (self.from[slot], self.to[slot]);
+
+            if paths.dist[u] == DIST_INF {
+                continue;
+            }
+
+            let next = paths.dist[u] + self.weight[slot];
+
+            if next < paths.dist[v] {
+                paths.dist[v] = next;
+                paths.parent[v] = Some((u, self.id[slot]));
+                ret = Some(v);
+            }
+        }
+
+        ret
+    }
+
+    // Vertices of some negative cycle in order, anywhere in the graph
+    fn negative_cycle(&self) -> Option<Vec<usize>> {
+        let n = self.n();
+        let mut paths = ShortestPaths::new(n);
+        let mut last = None;
+
+        // Every vertex starts at 0 as if joined to a virtual source
+        paths.dist.fill(0);
+
+        for _ in 0..n {
+            last = Some(self.relax_all(&mut paths)?);
+        }
+
+        // Still improving after n rounds, so n steps back from it land on the cycle
+        let mut vertex = last?;
+
+        for _ in 0..n {
+            vertex = paths.parent[vertex]?.0;
+        }
+
+        let mut ret = vec![vertex];
+        let mut cur = paths.parent[vertex]?.0;
+
+        while cur != vertex {
+            ret.push(cur);
+            cur = paths.parent[cur]?.0;
+        }
+
+        ret.reverse();
+        Some(ret)
+    }
+
+    // Reference: https://en.wikipedia.org/wiki/Johnson%27s_algorithm
+    // Shortest paths from every vertex in O(nm log n), None on a negative cycle
+    fn johnson(&self) -> Option<Vec<ShortestPaths>> {
+        let n = self.n();
+        let potential = self.bellman_ford(&(0..n).collect::<Vec<_>>()).dist;
+
+        if potential.contains(&DIST_NEG_INF) {
+            return None;
+        }
+
+        let ret = (0..n)
+            .map(|s| {
+                let mut paths = self.process_dijkstra(&[(s, 0)], |u, slot| {
+                    Some(self.weight[slot] + potential[u] - potential[self.to[slot]])
+                });
+
+                for v in 0..n {
+                    if paths.dist[v] != DIST_INF {
+                        paths.dist[v] += potential[v] - potential[s];
+                    }
+                }
+
+                paths
+            })
+            .collect();
+
+        Some(ret)
+    }
+
+    // Reference: https://en.wikipedia.org/wiki/Yen%27s_algorithm
+    // The k cheapest simple paths from any source to target as (cost, vertices)
+    // Parallel edges give distinct paths, weights must be nonnegative
+    fn k_shortest_paths(
+        &self,
+        sources: &[usize],
+        target: usize,
+        k: usize,
+    ) -> Vec<(i64, Vec<usize>)> {
+        // A path is its cost, its first vertex and its slots
+        let mut found: Vec<(i64, usize, Vec<usize>)> = Vec::new();
+        let mut candidates = BinaryHeap::new();
+        let mut seen = HashSet::new();
+        let mut banned_vertex = vec![false; self.n()];
+        let mut banned_slot = vec![false; self.to.len()];
+
+        // First vertex and slots of the path a search found to target
+        let slots_of = |paths: &ShortestPaths| {
+            let mut slots = Vec::new();
+            let mut vertex = target;
+
+            while let Some((prev, edge)) = paths.parent[vertex] {
+                let slot = self
+                    .edges(prev)
+                    .find(|&slot| self.to[slot] == vertex && self.id[slot] == edge)
+                    .unwrap();
+
+                slots.push(slot);
+                vertex = prev;
+            }
+
+            slots.reverse();
+            (vertex, slots)
+        };
+
+        while found.len() < k {
+            // Paths through a virtual root joined to every source, the spur at the root
+            // may only start from sources no found path starts from
+            let roots = sources
+                .iter()
+                .filter(|&&s| found.iter().all(|&(_, first, _)| first != s))
+                .map(|&s| (s, 0))
+                .collect::<Vec<_>>();
+            let paths = self.process_dijkstra(&roots, |_, slot| Some(self.weight[slot]));
+
+            if paths.reachable(target) {
+                let (first, slots) = slots_of(&paths);
+
+                if seen.insert((first, slots.clone())) {
+                    candidates.push(Reverse((paths.dist[target], first, slots)));
+                }
+            }
+
+            if let Some((_, first, slots)) = found.last().cloned() {
+                let mut spur = first;
+                let mut root_cost = 0;
+
+                // The spur leaves the last path after its first j slots
+                for j in 0..slots.len() {
+                    for (_, other_first, other) in found.iter() {
+                        if *other_first == first && other.len() > j && other[..j] == slots[..j] {
+                            banned_slot[other[j]] = true;
+                        }
+                    }
+
+                    let paths = self.process_dijkstra(&[(spur, 0)], |_, slot| {
+                        if banned_slot[slot] || banned_vertex[self.to[slot]] {
+                            None
+                        } else {
+                            Some(self.weight[slot])
+                        }
+                    });
+
+                    if paths.reachable(target) {
+                        let mut next = slots[..j].to_vec();
+                        next.extend(slots_of(&paths).1);
+
+                        if seen.insert((first, next.clone())) {
+                            candidates.push(Reverse((root_cost + paths.dist[target], first, next)));
+                        }
+                    }
+
+                    for (_, _, other) in found.iter() {
+                        if other.len() > j {
+                            banned_slot[other[j]] = false;
+                        }
+                    }
+
+                    banned_vertex[spur] = true;
+                    root_cost += self.weight[slots[j]];
+                    spur = self.to[slots[j]];
+                }
+
+                banned_vertex.fill(false);
+            }
+
+            match candidates.pop() {
+                Some(Reverse(path)) => found.push(path),
+                None => break,
+            }
+        }
+
+        found
+            .into_iter()
+            .map(|(cost, first, slots)| {
+                let vertices = std::iter::once(first)
+                    .chain(slots.iter().map(|&slot| self.to[slot]))
+                    .collect();
+
+                (cost, vertices)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,337 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::ops::Range;
+
+// fail[i] = length of the longest proper border of s[..=i]
+fn prefix_function<T: Eq>(s: &[T]) -> Vec<usize> {
+    let mut fail = vec![0; s.len()];
+    let mut j = 0;
+
+    for i in 1..s.len() {
+        while j > 0 && s[i] != s[j] {
+            j = fail[j - 1];
+        }
+
+        if s[i] == s[j] {
+            j += 1;
+        }
+
+        fail[i] = j;
+    }
+
+    fail
+}
+
+// Starting positions of all occurrences of pattern in text
+fn process_kmp<T: Eq>(text: &[T], pattern: &[T]) -> Vec<usize> {
+    if pattern.is_empty() {
+        return (0..=text.len()).collect();
+    }
+
+    let fail = prefix_function(pattern);
+    let mut ret = Vec::new();
+    let mut j = 0;
+
+    for (i, c) in text.iter().enumerate() {
+        while j > 0 && *c != pattern[j] {
+            j = fail[j - 1];
+        }
+
+        if *c == pattern[j] {
+            j += 1;
+        }
+
+        if j == pattern.len() {
+            ret.push(i + 1 - j);
+            j = fail[j - 1];
+        }
+    }
+
+    ret
+}
+
+// z[i] = LCP of s and s[i..], z[0] = s.len()
+fn z_function<T: Eq>(s: &[T]) -> Vec<usize> {
+    let n = s.len();
+    let mut z = vec![0; n];
+
+    if n == 0 {
+        return z;
+    }
+
+    z[0] = n;
+
+    let mut left = 0;
+    let mut right = 0;
+
+    for i in 1..n {
+        if i < right {
+            z[i] = z[i - left].min(right - i);
+        }
+
+        while i + z[i] < n && s[z[i]] == s[i + z[i]] {
+            z[i] += 1;
+        }
+
+        if i + z[i] > right {
+            left = i;
+            right = i + z[i];
+        }
+    }
+
+    z
+}
+
+// Reference: http://www.secmem.org/blog/2019/03/10/Manacher/
+// Runs on s with separators between characters, ret[i] is the palindrome length in s centered at i
+fn process_manachers<T: Eq>(text: &[T]) -> Vec<usize> {
+    let len = 2 * text.len() + 1;
+    let same = |a: usize, b: usize| a % 2 == b % 2 && ((a & 1) == 0 || text[a / 2] == text[b / 2]);
+    let mut ret = vec![0; len];
+    let mut r = 0;
+    let mut c = 0;
+
+    for i in 0..len {
+        ret[i] = if r < i { 0 } else { ret[2 * c - i].min(r - i) };
+
+        while i > ret[i] && i + ret[i] + 1 < len && same(i - ret[i] - 1, i + ret[i] + 1) {
+            ret[i] += 1;
+        }
+
+        if r < i + ret[i] {
+            r = i + ret[i];
+            c = i;
+        }
+    }
+
+    ret
+}
+
+fn longest_palindrome<T: Eq>(text: &[T]) -> Range<usize> {
+    let radius = process_manachers(text);
+    let (center, len) = radius
+        .iter()
+        .enumerate()
+        .max_by_key(|&(i, &len)| (len, std::cmp::Reverse(i)))
+        .map(|(i, &len)| (i, len))
+        .unwrap();
+
+    (center - len) / 2..(center + len) / 2
+}
+
+#[derive(Clone)]
+struct AhoCorasickNode<T> {
+    next: BTreeMap<T, usize>,
+    fail: usize,
+    output_link: Option<usize>,
+    terminal: Vec<usize>,
+    count: u64,
+}
+
+impl<T> AhoCorasickNode<T> {
+    fn new() -> Self {
+        Self {
+            next: BTreeMap::new(),
+            fail: 0,
+            output_link: None,
+            terminal: Vec::new(),
+            count: 0,
+        }
+    }
+}
+
+struct AhoCorasick<T> {
+    nodes: Vec<AhoCorasickNode<T>>,
+    order: Vec<usize>,
+    num_patterns: usize,
+}
+
+impl<T: Ord + Copy> AhoCorasick<T> {
+    fn new() -> Self {
+        Self {
+            nodes: vec![AhoCorasickNode::new()],
+            order: Vec::new(),
+            num_patterns: 0,
+        }
+    }
+
+    // Returns the id of the pattern, build() must be called after the last insertion
+    fn insert(&mut self, pattern: &[T]) -> usize {
+        let mut cur = 0;
+
+        for &c in pattern {
+            cur = match self.nodes[cur].next.get(&c) {
+                Some(&next) => next,
+                None => {
+                    self.nodes.push(AhoCorasickNode::new());
+                    let next = self.nodes.len() - 1;
+                    self.nodes[cur].next.insert(c, next);
+                    next
+                }
+            };
+        }
+
+        self.nodes[cur].terminal.push(self.num_patterns);
+        self.num_patterns += 1;
+
+        self.num_patterns - 1
+    }
+
+    fn build(&mut self) {
+        let mut queue = VecDeque::new();
+        self.order.clear();
+        queue.push_back(0);
+
+        // An empty pattern ends at the root and so after every character
+        self.nodes[0].count = self.nodes[0].terminal.len() as u64;
+
+        while let Some(cur) = queue.pop_front() {
+            self.order.push(cur);
+
+            let children = self.nodes[cur]
+                .next
+                .iter()
+                .map(|(&c, &next)| (c, next))
+                .collect::<Vec<_>>();
+
+            for (c, next) in children {
+                let fail = if cur == 0 {
+                    0
+                } else {
+                    let mut f = self.nodes[cur].fail;
+
+                    loop {
+                        if let Some(&target) = self.nodes[f].next.get(&c) {
+                            break target;
+                        }
+
+                        if f == 0 {
+                            break 0;
+                        }
+
+                        f = self.nodes[f].fail;
+                    }
+                };
+
+                self.nodes[next].fail = fail;
+                self.nodes[next].output_link = if self.nodes[fail].terminal.is_empty() {
+                    self.nodes[fail].output_link
+                } else {
+                    Some(fail)
+                };
+                self.nodes[next].count =
+                    self.nodes[next].terminal.len() as u64 + self.nodes[fail].count;
+
+                queue.push_back(next);
+            }
+        }
+    }
+
+    fn next_state(&self, mut state: usize, c: T) -> usize {
+        loop {
+            if let Some(&next) = self.nodes[state].next.get(&c) {
+                return next;
+            }
+
+            if state == 0 {
+                return 0;
+            }
+
+            state = self.nodes[state].fail;
+        }
+    }
+
+    // Number of pattern occurrences ending at a node
+    fn matches_at(&self, state: usize) -> u64 {
+        self.nodes[state].count
+    }
+
+    fn contains_any(&self, text: &[T]) -> bool {
+        let mut state = 0;
+
+        text.iter().any(|&c| {
+            state = self.next_state(state, c);
+            self.nodes[state].count > 0
+        })
+    }
+
+    fn count_matches(&self, text: &[T]) -> u64 {
+        let mut state = 0;
+
+        text.iter()
+            .map(|&c| {
+                state = self.next_state(state, c);
+                self.nodes[state].count
+            })
+            .sum()
+    }
+
+    // Every occurrence as (end position exclusive, pattern id), walking output links
+    fn find_all(&self, text: &[T]) -> Vec<(usize, usize)> {
+        let mut ret = Vec::new();
+        let mut state = 0;
+
+        for (i, &c) in text.iter().enumerate() {
+            state = self.next_state(state, c);
+
+            let mut node = if self.nodes[state].terminal.is_empty() {
+                self.nodes[state].output_link
+            } else {
+                Some(state)
+            };
+
+            while let Some(cur) = node {
+                for &id in self.nodes[cur].terminal.iter() {
+                    ret.push((i + 1, id));
+                }
+
+                node = self.nodes[cur].output_link;
+            }
+        }
+
+        ret
+    }
+
+    // Occurrence count of each pattern, in O(|text| + nodes)
+    fn count_each(&self, text: &[T]) -> Vec<u64> {
+        let mut visited = vec![0; self.nodes.len()];
+        let mut state = 0;
+
+        for &c in text {
+            state = self.next_state(state, c);
+            visited[state] += 1;
+        }
+
+        for &cur in self.order.iter().rev() {
+            if cur != 0 {
+                visited[self.nodes[cur].fail] += visited[cur];
+            }
+        }
+
+        let mut ret = vec![0; self.num_patterns];
+
+        for (cur, node) in self.nodes.iter().enumerate() {
+            for &id in node.terminal.iter() {
+                ret[id] = visited[cur];
+            }
+        }
+
+        ret
+    }
+
+    // table[state][i] = next state after alphabet[i], for DP over strings avoiding patterns
+    fn transition_table(&self, alphabet: &[T]) -> Vec<Vec<usize>> {
+        let mut table = vec![vec![0; alphabet.len()]; self.nodes.len()];
+
+        for &cur in self.order.iter() {
+            for (i, c) in alphabet.iter().enumerate() {
+                table[cur][i] = match self.nodes[cur].next.get(c) {
+                    Some(&next) => next,
+                    None if cur == 0 => 0,
+                    None => table[self.nodes[cur].fail][i],
+                };
+            }
+        }
+
+        table
+    }
+}
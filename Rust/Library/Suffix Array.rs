@@ -0,0 +1,416 @@
+use std::ops::Range;
+
+// Reference: https://github.com/atcoder/ac-library/blob/master/atcoder/string.hpp
+// Input values must be in 0..=upper
+fn sa_is(s: &[usize], upper: usize) -> Vec<usize> {
+    let n = s.len();
+
+    match n {
+        0 => return Vec::new(),
+        1 => return vec![0],
+        2 => return if s[0] < s[1] { vec![0, 1] } else { vec![1, 0] },
+        _ => {}
+    }
+
+    let mut ls = vec![false; n];
+
+    for i in (0..n - 1).rev() {
+        ls[i] = if s[i] == s[i + 1] {
+            ls[i + 1]
+        } else {
+            s[i] < s[i + 1]
+        };
+    }
+
+    let mut sum_l = vec![0; upper + 2];
+    let mut sum_s = vec![0; upper + 2];
+
+    for i in 0..n {
+        if !ls[i] {
+            sum_s[s[i]] += 1;
+        } else {
+            sum_l[s[i] + 1] += 1;
+        }
+    }
+
+    for i in 0..=upper {
+        sum_s[i] += sum_l[i];
+        sum_l[i + 1] += sum_s[i];
+    }
+
+    let induce = |lms: &[usize], sa: &mut Vec<usize>| {
+        sa.iter_mut().for_each(|x| *x = usize::MAX);
+
+        let mut buf = sum_s.clone();
+
+        for &d in lms {
+            if d == n {
+                continue;
+            }
+
+            sa[buf[s[d]]] = d;
+            buf[s[d]] += 1;
+        }
+
+        buf.copy_from_slice(&sum_l);
+        sa[buf[s[n - 1]]] = n - 1;
+        buf[s[n - 1]] += 1;
+
+        for i in 0..n {
+            let v = sa[i];
+
+            if v != usize::MAX && v >= 1 && !ls[v - 1] {
+                sa[buf[s[v - 1]]] = v - 1;
+                buf[s[v - 1]] += 1;
+            }
+        }
+
+        buf.copy_from_slice(&sum_l);
+
+        for i in (0..n).rev() {
+            let v = sa[i];
+
+            if v != usize::MAX && v >= 1 && ls[v - 1] {
+                buf[s[v - 1] + 1] -= 1;
+                sa[buf[s[v - 1] + 1]] = v - 1;
+            }
+        }
+    };
+
+    let mut lms_map = vec![usize::MAX; n + 1];
+    let mut lms = Vec::new();
+
+    for i in 1..n {
+        if !ls[i - 1] && ls[i] {
+            lms_map[i] = lms.len();
+            lms.push(i);
+        }
+    }
+
+    let m = lms.len();
+    let mut sa = vec![usize::MAX; n];
+
+    induce(&lms, &mut sa);
+
+    if m > 0 {
+        let mut sorted_lms = sa
+            .iter()
+            .filter(|&&v| v != usize::MAX && lms_map[v] != usize::MAX)
+            .copied()
+            .collect::<Vec<_>>();
+        let mut rec_s = vec![0; m];
+        let mut rec_upper = 0;
+
+        for i in 1..m {
+            let mut l = sorted_lms[i - 1];
+            let mut r = sorted_lms[i];
+            let end_l = if lms_map[l] + 1 < m {
+                lms[lms_map[l] + 1]
+            } else {
+                n
+            };
+            let end_r = if lms_map[r] + 1 < m {
+                lms[lms_map[r] + 1]
+            } else {
+                n
+            };
+
+            let same = if end_l - l != end_r - r {
+                false
+            } else {
+                while l < end_l && s[l] == s[r] {
+                    l += 1;
+                    r += 1;
+                }
+
+                l < n && r < n && s[l] == s[r]
+            };
+
+            if !same {
+                rec_upper += 1;
+            }
+
+            rec_s[lms_map[sorted_lms[i]]] = rec_upper;
+        }
+
+        let rec_sa = sa_is(&rec_s, rec_upper);
+
+        for i in 0..m {
+            sorted_lms[i] = lms[rec_sa[i]];
+        }
+
+        induce(&sorted_lms, &mut sa);
+    }
+
+    sa
+}
+
+fn build_suffix_array(s: &[u8]) -> Vec<usize> {
+    let s = s.iter().map(|&c| c as usize).collect::<Vec<_>>();
+    sa_is(&s, 255)
+}
+
+// Compresses an arbitrary ordered alphabet before running SA-IS
+fn build_suffix_array_of_ints<T: Ord>(s: &[T]) -> Vec<usize> {
+    let mut idx = (0..s.len()).collect::<Vec<_>>();
+    idx.sort_by(|&a, &b| s[a].cmp(&s[b]));
+
+    let mut compressed = vec![0; s.len()];
+    let mut upper = 0;
+
+    for i in 0..idx.len() {
+        if i > 0 && s[idx[i - 1]] != s[idx[i]] {
+            upper += 1;
+        }
+
+        compressed[idx[i]] = upper;
+    }
+
+    sa_is(&compressed, upper)
+}
+
+// Reference: http://www.secmem.org/blog/2021/07/18/suffix-array-and-lcp/
+// lcp_array[i] = LCP(suffix_array[i - 1], suffix_array[i]), lcp_array[0] = 0
+fn build_lcp_array<T: Eq>(s: &[T], suffix_array: &[usize]) -> Vec<usize> {
+    let n = s.len();
+    let mut lcp_array = vec![0; n];
+    let mut rank = vec![0; n];
+
+    for i in 0..n {
+        rank[suffix_array[i]] = i;
+    }
+
+    let mut k = 0;
+
+    for i in 0..n {
+        if rank[i] == 0 {
+            k = 0;
+            continue;
+        }
+
+        let j = suffix_array[rank[i] - 1];
+
+        while i + k < n && j + k < n && s[i + k] == s[j + k] {
+            k += 1;
+        }
+
+        lcp_array[rank[i]] = k;
+        k = k.saturating_sub(1);
+    }
+
+    lcp_array
+}
+
+struct SuffixArray {
+    text: Vec<usize>,
+    suffix_array: Vec<usize>,
+    rank: Vec<usize>,
+    lcp_array: Vec<usize>,
+    sparse: Vec<Vec<usize>>,
+}
+
+impl SuffixArray {
+    fn new(s: &[u8]) -> Self {
+        Self::from_ints(&s.iter().map(|&c| c as usize).collect::<Vec<_>>(), 255)
+    }
+
+    // Values of s must be in 0..=upper
+    fn from_ints(s: &[usize], upper: usize) -> Self {
+        let n = s.len();
+        let suffix_array = sa_is(s, upper);
+        let lcp_array = build_lcp_array(s, &suffix_array);
+        let mut rank = vec![0; n];
+
+        for i in 0..n {
+            rank[suffix_array[i]] = i;
+        }
+
+        let mut sparse = vec![lcp_array.clone()];
+        let mut k = 1;
+
+        while 2 * k <= n {
+            let prev = &sparse[sparse.len() - 1];
+            let next = (0..=n - 2 * k)
+                .map(|i| prev[i].min(prev[i + k]))
+                .collect::<Vec<_>>();
+
+            sparse.push(next);
+            k *= 2;
+        }
+
+        Self {
+            text: s.to_vec(),
+            suffix_array,
+            rank,
+            lcp_array,
+            sparse,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    // Minimum of lcp_array[left..right]
+    fn range_min(&self, left: usize, right: usize) -> usize {
+        let level = (usize::BITS - 1 - (right - left).leading_zeros()) as usize;
+        self.sparse[level][left].min(self.sparse[level][right - (1 << level)])
+    }
+
+    // Longest common prefix of the suffixes starting at i and j
+    fn lcp(&self, i: usize, j: usize) -> usize {
+        if i == self.len() || j == self.len() {
+            return 0;
+        }
+
+        if i == j {
+            return self.len() - i;
+        }
+
+        let (a, b) = if self.rank[i] < self.rank[j] {
+            (self.rank[i], self.rank[j])
+        } else {
+            (self.rank[j], self.rank[i])
+        };
+
+        self.range_min(a + 1, b + 1)
+    }
+
+    // Compares text[a] and text[b] in O(1)
+    fn compare(&self, a: Range<usize>, b: Range<usize>) -> std::cmp::Ordering {
+        let len_a = a.end - a.start;
+        let len_b = b.end - b.start;
+        let common = self.lcp(a.start, b.start);
+
+        if common >= len_a.min(len_b) {
+            len_a.cmp(&len_b)
+        } else {
+            self.rank[a.start].cmp(&self.rank[b.start])
+        }
+    }
+
+    fn count_distinct_substrings(&self) -> u64 {
+        let n = self.len() as u64;
+        n * (n + 1) / 2 - self.lcp_array.iter().map(|&x| x as u64).sum::<u64>()
+    }
+
+    // Returns (start, length) of a longest substring occurring at least twice
+    fn longest_repeated_substring(&self) -> (usize, usize) {
+        let mut ret = (0, 0);
+
+        for i in 1..self.len() {
+            if self.lcp_array[i] > ret.1 {
+                ret = (self.suffix_array[i], self.lcp_array[i]);
+            }
+        }
+
+        ret
+    }
+
+    // Range of suffix_array whose suffixes start with pattern, in O(|pattern| log n)
+    fn find(&self, pattern: &[usize]) -> Range<usize> {
+        let prefix = |pos: usize| {
+            let end = (pos + pattern.len()).min(self.len());
+            &self.text[pos..end]
+        };
+
+        let left = self
+            .suffix_array
+            .partition_point(|&pos| prefix(pos) < pattern);
+        let right = self
+            .suffix_array
+            .partition_point(|&pos| prefix(pos) <= pattern);
+
+        left..right
+    }
+
+    fn find_bytes(&self, pattern: &[u8]) -> Range<usize> {
+        self.find(&pattern.iter().map(|&c| c as usize).collect::<Vec<_>>())
+    }
+
+    // Sorted starting positions of all occurrences of pattern
+    fn occurrences(&self, pattern: &[usize]) -> Vec<usize> {
+        let mut ret = self.suffix_array[self.find(pattern)].to_vec();
+        ret.sort_unstable();
+        ret
+    }
+}
+
+// Returns (index of string, start, length) of a longest substring common to all strings
+fn longest_common_substring(strings: &[&[u8]]) -> (usize, usize, usize) {
+    let k = strings.len();
+
+    if k == 0 {
+        return (0, 0, 0);
+    }
+
+    // Each string is followed by its own separator so that no LCP crosses a boundary
+    let mut text = Vec::new();
+    let mut owner = Vec::new();
+    let mut offset = Vec::new();
+
+    for (idx, s) in strings.iter().enumerate() {
+        offset.push(text.len());
+
+        for &c in s.iter() {
+            text.push(c as usize + k);
+            owner.push(idx);
+        }
+
+        text.push(idx);
+        owner.push(usize::MAX);
+    }
+
+    let sa = SuffixArray::from_ints(&text, 255 + k);
+    let n = text.len();
+    let mut cnt = vec![0; k];
+    let mut covered = 0;
+    let mut ret = (0, 0, 0);
+    let mut left = 0;
+
+    for right in 0..n {
+        let o = owner[sa.suffix_array[right]];
+
+        if o == usize::MAX {
+            continue;
+        }
+
+        if cnt[o] == 0 {
+            covered += 1;
+        }
+
+        cnt[o] += 1;
+
+        if covered < k {
+            continue;
+        }
+
+        loop {
+            let lo = owner[sa.suffix_array[left]];
+
+            if lo != usize::MAX && cnt[lo] == 1 {
+                break;
+            }
+
+            if lo != usize::MAX {
+                cnt[lo] -= 1;
+            }
+
+            left += 1;
+        }
+
+        let common = if left == right {
+            let pos = sa.suffix_array[left];
+            strings[owner[pos]].len() - (pos - offset[owner[pos]])
+        } else {
+            sa.range_min(left + 1, right + 1)
+        };
+
+        if common > ret.2 {
+            let pos = sa.suffix_array[right];
+            ret = (owner[pos], pos - offset[owner[pos]], common);
+        }
+    }
+
+    ret
+}
@@ -0,0 +1,289 @@
+use std::collections::BTreeMap;
+
+const NONE: usize = usize::MAX;
+
+#[derive(Clone)]
+struct State<T> {
+    len: usize,
+    link: usize,
+    first_pos: usize,
+    next: BTreeMap<T, usize>,
+}
+
+// Reference: https://cp-algorithms.com/string/suffix-automaton.html
+struct SuffixAutomaton<T> {
+    states: Vec<State<T>>,
+    cnt: Vec<u64>,
+    last: usize,
+}
+
+impl<T: Ord + Copy> SuffixAutomaton<T> {
+    fn new() -> Self {
+        Self {
+            states: vec![State {
+                len: 0,
+                link: NONE,
+                first_pos: NONE,
+                next: BTreeMap::new(),
+            }],
+            cnt: vec![0],
+            last: 0,
+        }
+    }
+
+    fn from_slice(s: &[T]) -> Self {
+        let mut automaton = Self::new();
+
+        for &c in s {
+            automaton.push(c);
+        }
+
+        automaton
+    }
+
+    // Generalized automaton over several strings, each one is started from the root
+    fn from_slices(strings: &[&[T]]) -> Self {
+        let mut automaton = Self::new();
+
+        for s in strings {
+            automaton.reset_last();
+
+            for &c in s.iter() {
+                automaton.push(c);
+            }
+        }
+
+        automaton
+    }
+
+    fn reset_last(&mut self) {
+        self.last = 0;
+    }
+
+    fn clone_state(&mut self, p: usize, q: usize, c: T) -> usize {
+        let mut state = self.states[q].clone();
+        state.len = self.states[p].len + 1;
+
+        let clone = self.states.len();
+        self.states.push(state);
+        self.cnt.push(0);
+
+        let mut p = p;
+
+        while p != NONE && self.states[p].next.get(&c) == Some(&q) {
+            self.states[p].next.insert(c, clone);
+            p = self.states[p].link;
+        }
+
+        self.states[q].link = clone;
+
+        clone
+    }
+
+    // Appends c to the current string, first_pos of a new state is its end index in that string
+    fn push(&mut self, c: T) -> usize {
+        let last = self.last;
+
+        if let Some(&q) = self.states[last].next.get(&c) {
+            let ret = if self.states[last].len + 1 == self.states[q].len {
+                q
+            } else {
+                self.clone_state(last, q, c)
+            };
+
+            self.cnt[ret] += 1;
+            self.last = ret;
+            return ret;
+        }
+
+        let cur = self.states.len();
+        self.states.push(State {
+            len: self.states[last].len + 1,
+            link: 0,
+            first_pos: self.states[last].len,
+            next: BTreeMap::new(),
+        });
+        self.cnt.push(1);
+
+        let mut p = last;
+
+        while p != NONE && !self.states[p].next.contains_key(&c) {
+            self.states[p].next.insert(c, cur);
+            p = self.states[p].link;
+        }
+
+        if p != NONE {
+            let q = self.states[p].next[&c];
+
+            self.states[cur].link = if self.states[p].len + 1 == self.states[q].len {
+                q
+            } else {
+                self.clone_state(p, q, c)
+            };
+        }
+
+        self.last = cur;
+        cur
+    }
+
+    // States sorted by len, so every link comes before the state itself
+    fn order(&self) -> Vec<usize> {
+        let max_len = self.states.iter().map(|s| s.len).max().unwrap();
+        let mut bucket = vec![0; max_len + 2];
+
+        for state in self.states.iter() {
+            bucket[state.len + 1] += 1;
+        }
+
+        for i in 1..bucket.len() {
+            bucket[i] += bucket[i - 1];
+        }
+
+        let mut ret = vec![0; self.states.len()];
+
+        for (i, state) in self.states.iter().enumerate() {
+            ret[bucket[state.len]] = i;
+            bucket[state.len] += 1;
+        }
+
+        ret
+    }
+
+    // Size of the endpos set of every state
+    fn occurrences(&self) -> Vec<u64> {
+        let mut ret = self.cnt.clone();
+
+        for &v in self.order().iter().skip(1).rev() {
+            ret[self.states[v].link] += ret[v];
+        }
+
+        ret
+    }
+
+    fn walk(&self, pattern: &[T]) -> Option<usize> {
+        let mut cur = 0;
+
+        for c in pattern {
+            cur = *self.states[cur].next.get(c)?;
+        }
+
+        Some(cur)
+    }
+
+    fn contains(&self, pattern: &[T]) -> bool {
+        self.walk(pattern).is_some()
+    }
+
+    // End index of the first occurrence of a non-empty pattern
+    fn first_occurrence(&self, pattern: &[T]) -> Option<usize> {
+        self.walk(pattern).map(|v| self.states[v].first_pos)
+    }
+
+    fn count_distinct_substrings(&self) -> u64 {
+        self.states
+            .iter()
+            .skip(1)
+            .map(|s| (s.len - self.states[s.link].len) as u64)
+            .sum()
+    }
+
+    // k-th (1-indexed) lexicographically smallest non-empty substring
+    // Distinct substrings are counted once, otherwise every occurrence is counted
+    fn kth_substring(&self, k: u64, distinct: bool) -> Option<Vec<T>> {
+        let weight = if distinct {
+            vec![1; self.states.len()]
+        } else {
+            self.occurrences()
+        };
+        let mut paths = weight.clone();
+        paths[0] = 0;
+
+        for &v in self.order().iter().rev() {
+            for &next in self.states[v].next.values() {
+                paths[v] += paths[next];
+            }
+        }
+
+        if k == 0 || k > paths[0] {
+            return None;
+        }
+
+        let mut ret = Vec::new();
+        let mut cur = 0;
+        let mut k = k;
+
+        loop {
+            if cur != 0 {
+                if k <= weight[cur] {
+                    return Some(ret);
+                }
+
+                k -= weight[cur];
+            }
+
+            for (&c, &next) in self.states[cur].next.iter() {
+                if k <= paths[next] {
+                    ret.push(c);
+                    cur = next;
+                    break;
+                }
+
+                k -= paths[next];
+            }
+        }
+    }
+}
+
+// Longest substring common to all strings
+fn longest_common_substring<T: Ord + Copy>(strings: &[&[T]]) -> Vec<T> {
+    if strings.is_empty() {
+        return Vec::new();
+    }
+
+    let automaton = SuffixAutomaton::from_slice(strings[0]);
+    let order = automaton.order();
+    let states = &automaton.states;
+    let mut best = states.iter().map(|s| s.len).collect::<Vec<_>>();
+
+    for s in strings.iter().skip(1) {
+        let mut matched = vec![0; states.len()];
+        let mut cur = 0;
+        let mut len = 0;
+
+        for c in s.iter() {
+            while cur != 0 && !states[cur].next.contains_key(c) {
+                cur = states[cur].link;
+                len = states[cur].len;
+            }
+
+            if let Some(&next) = states[cur].next.get(c) {
+                cur = next;
+                len += 1;
+            }
+
+            matched[cur] = matched[cur].max(len);
+        }
+
+        for &v in order.iter().skip(1).rev() {
+            let link = states[v].link;
+            matched[link] = matched[link].max(matched[v].min(states[link].len));
+        }
+
+        for v in 0..states.len() {
+            best[v] = best[v].min(matched[v]);
+        }
+    }
+
+    let (v, &len) = best
+        .iter()
+        .enumerate()
+        .max_by_key(|&(_, &len)| len)
+        .unwrap();
+
+    if len == 0 {
+        return Vec::new();
+    }
+
+    let end = states[v].first_pos + 1;
+    strings[0][end - len..end].to_vec()
+}